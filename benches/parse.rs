@@ -0,0 +1,23 @@
+use chrono::TimeZone;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::path::Path;
+use tori_scrape::utils;
+use tori_scrape::Parser;
+
+fn bench_parse_from_string(c: &mut Criterion) {
+    let tz = utils::timezone_lookup("Europe/Helsinki").unwrap();
+    let fetch_time = tz.with_ymd_and_hms(2023, 3, 25, 10, 52, 1).unwrap();
+
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("src/testdata/2023-03-25-105201-dump.html");
+    let buf = utils::decode_to_string(&path, utils::encoding_lookup("ISO_8859_15").unwrap());
+
+    let parser = Parser::new(fetch_time);
+
+    c.bench_function("parse_from_string 2023-03-25 (40 items)", |b| {
+        b.iter(|| parser.parse_from_string(black_box(&buf)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_parse_from_string);
+criterion_main!(benches);
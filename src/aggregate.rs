@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::parsing::{Direction, Item, ItemWarning};
+
+/// Groups items by their (trimmed) location, preserving the order of items within each
+/// group.
+pub fn group_by_location(items: &[Item]) -> HashMap<String, Vec<&Item>> {
+    let mut groups: HashMap<String, Vec<&Item>> = HashMap::new();
+    for item in items {
+        groups
+            .entry(item.location.trim().to_string())
+            .or_default()
+            .push(item);
+    }
+    groups
+}
+
+/// Summary of the prices of a set of items, see [price_stats].
+#[derive(Debug, PartialEq)]
+pub struct PriceStats {
+    /// Number of priced items.
+    pub count: usize,
+    pub min: i32,
+    pub max: i32,
+    pub mean: f64,
+    /// Middle value, or the mean of the two middle values for an even count.
+    pub median: f64,
+}
+
+/// Computes statistics of `price.value` over the items that have a price. Returns `None` if
+/// none of them do. For price ranges the lower bound is used.
+pub fn price_stats(items: &[Item]) -> Option<PriceStats> {
+    let mut values: Vec<i32> = items
+        .iter()
+        .filter_map(|item| item.price.as_ref().map(|p| p.value))
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+
+    let count = values.len();
+    // accumulate in i64, summing many large i32 prices would overflow.
+    let sum: i64 = values.iter().map(|&v| v as i64).sum();
+    let median = if count % 2 == 0 {
+        (values[count / 2 - 1] as f64 + values[count / 2] as f64) / 2.0
+    } else {
+        values[count / 2] as f64
+    };
+
+    Some(PriceStats {
+        count,
+        min: values[0],
+        max: values[count - 1],
+        mean: sum as f64 / count as f64,
+        median,
+    })
+}
+
+/// Item counts of a scrape, see [summarize].
+#[derive(Debug, Default, PartialEq)]
+pub struct ScrapeSummary {
+    pub total: usize,
+    /// Items with a numeric price.
+    pub with_price: usize,
+    pub company_ads: usize,
+    pub private_ads: usize,
+    /// Items with [Direction::Selling].
+    pub selling: usize,
+    /// Items with [Direction::Buying].
+    pub buying: usize,
+}
+
+/// Counts the items by seller type and direction, and how many have a price.
+pub fn summarize(items: &[Item]) -> ScrapeSummary {
+    let mut summary = ScrapeSummary {
+        total: items.len(),
+        ..ScrapeSummary::default()
+    };
+    for item in items {
+        if item.price.is_some() {
+            summary.with_price += 1;
+        }
+        if item.is_company_ad {
+            summary.company_ads += 1;
+        } else {
+            summary.private_ads += 1;
+        }
+        match item.direction_kind() {
+            Direction::Selling => summary.selling += 1,
+            Direction::Buying => summary.buying += 1,
+            _ => {}
+        }
+    }
+    summary
+}
+
+/// Changes between two scrapes of the same search, see [diff].
+#[derive(Debug)]
+pub struct ScrapeDiff<'a> {
+    /// Items only in the new scrape.
+    pub added: Vec<&'a Item>,
+    /// Items only in the old scrape.
+    pub removed: Vec<&'a Item>,
+    /// Items in both scrapes whose price differs, as (old, new) pairs.
+    pub price_changed: Vec<(&'a Item, &'a Item)>,
+}
+
+/// Compares two scrapes, matching items by `item_id`. Added and changed items are listed in
+/// the order of `new`, removed items in the order of `old`.
+pub fn diff<'a>(old: &'a [Item], new: &'a [Item]) -> ScrapeDiff<'a> {
+    let old_by_id: HashMap<&str, &Item> = old.iter().map(|i| (i.item_id.as_str(), i)).collect();
+    let new_by_id: HashMap<&str, &Item> = new.iter().map(|i| (i.item_id.as_str(), i)).collect();
+
+    let mut added = vec![];
+    let mut price_changed = vec![];
+    for item in new {
+        match old_by_id.get(item.item_id.as_str()) {
+            None => added.push(item),
+            Some(old_item) if old_item.price != item.price => price_changed.push((*old_item, item)),
+            Some(_) => {}
+        }
+    }
+    let removed = old
+        .iter()
+        .filter(|i| !new_by_id.contains_key(i.item_id.as_str()))
+        .collect();
+
+    ScrapeDiff {
+        added,
+        removed,
+        price_changed,
+    }
+}
+
+/// Splits items into those that pass [Item::validate] and those that do not, the latter
+/// together with their warnings. Both keep the original order.
+pub fn partition_valid(
+    items: Vec<Item>,
+    fetch_time: DateTime<Utc>,
+) -> (Vec<Item>, Vec<(Item, Vec<ItemWarning>)>) {
+    let mut valid = vec![];
+    let mut suspicious = vec![];
+    for item in items {
+        let warnings = item.validate(fetch_time);
+        if warnings.is_empty() {
+            valid.push(item);
+        } else {
+            suspicious.push((item, warnings));
+        }
+    }
+    (valid, suspicious)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+    use crate::testutil::RowBuilder;
+    use chrono::TimeZone;
+
+    /// Parses one item per (location, price text) pair.
+    fn items(rows: &[(&str, &str)]) -> Vec<Item> {
+        let helsinki = chrono_tz::Europe::Helsinki;
+        let parser = Parser::new(helsinki.with_ymd_and_hms(2023, 3, 25, 10, 52, 1).unwrap());
+        let buf: String = rows
+            .iter()
+            .enumerate()
+            .map(|(i, (location, price))| {
+                RowBuilder::new()
+                    .id(&i.to_string())
+                    .price(price)
+                    .cat_geo(&[*location, "Myydään"])
+                    .build()
+            })
+            .collect();
+        parser.parse_from_string(&buf).unwrap()
+    }
+
+    #[test]
+    fn test_group_by_location() {
+        let items = items(&[
+            ("Helsinki", "10 €"),
+            ("Espoo", "20 €"),
+            (" Helsinki ", "30 €"),
+            ("Helsinki", "40 €"),
+        ]);
+        let groups = group_by_location(&items);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups["Helsinki"].len(), 3);
+        assert_eq!(groups["Espoo"].len(), 1);
+        assert_eq!(groups["Helsinki"][1].item_id, "2");
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = items(&[
+            ("Helsinki", "10 €"),
+            ("Helsinki", "20 €"),
+            ("Helsinki", "30 €"),
+        ]);
+        // item 0 removed, 1 unchanged, 2 price changed, 3 added
+        let new: Vec<Item> = items(&[
+            ("Helsinki", "99 €"),
+            ("Helsinki", "20 €"),
+            ("Helsinki", "25 €"),
+            ("Helsinki", "40 €"),
+        ])
+        .into_iter()
+        .skip(1)
+        .collect();
+
+        let changes = diff(&old, &new);
+        let ids = |items: &[&Item]| items.iter().map(|i| i.item_id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&changes.added), vec!["3"]);
+        assert_eq!(ids(&changes.removed), vec!["0"]);
+        assert_eq!(changes.price_changed.len(), 1);
+        let (before, after) = changes.price_changed[0];
+        assert_eq!(before.item_id, "2");
+        assert_eq!(before.price.as_ref().map(|p| p.value), Some(30));
+        assert_eq!(after.price.as_ref().map(|p| p.value), Some(25));
+
+        let unchanged = diff(&old, &old);
+        assert!(unchanged.added.is_empty());
+        assert!(unchanged.removed.is_empty());
+        assert!(unchanged.price_changed.is_empty());
+    }
+
+    #[test]
+    fn test_price_stats() {
+        let items = items(&[
+            ("Helsinki", "40 €"),
+            ("Helsinki", ""),
+            ("Helsinki", "10 €"),
+            ("Helsinki", "30 €"),
+            ("Helsinki", "20 €"),
+        ]);
+        assert_eq!(
+            price_stats(&items),
+            Some(PriceStats {
+                count: 4,
+                min: 10,
+                max: 40,
+                mean: 25.0,
+                median: 25.0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_price_stats_no_prices() {
+        assert_eq!(price_stats(&[]), None);
+        assert_eq!(price_stats(&items(&[("Helsinki", "")])), None);
+    }
+
+    #[test]
+    fn test_price_stats_single() {
+        let stats = price_stats(&items(&[("Helsinki", "15 €")])).unwrap();
+        assert_eq!((stats.count, stats.min, stats.max), (1, 15, 15));
+        assert_eq!(stats.mean, 15.0);
+        assert_eq!(stats.median, 15.0);
+    }
+
+    #[test]
+    fn test_price_stats_no_overflow() {
+        let items = items(&[("Helsinki", "2000000000 €"), ("Helsinki", "2000000000 €")]);
+        let stats = price_stats(&items).unwrap();
+        assert_eq!(stats.mean, 2_000_000_000.0);
+    }
+
+    #[test]
+    fn test_partition_valid() {
+        let items = items(&[("Helsinki", "15 €"), ("Espoo", "0 €"), ("Vantaa", "20 €")]);
+        // the rows are posted at 12:34 local time
+        let fetch_time = chrono_tz::Europe::Helsinki
+            .with_ymd_and_hms(2023, 3, 25, 14, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let (valid, suspicious) = partition_valid(items, fetch_time);
+        let locations: Vec<&str> = valid.iter().map(|i| i.location.as_str()).collect();
+        assert_eq!(locations, vec!["Helsinki", "Vantaa"]);
+        assert_eq!(suspicious.len(), 1);
+        assert_eq!(suspicious[0].0.location, "Espoo");
+        assert_eq!(suspicious[0].1, vec![ItemWarning::ZeroPrice]);
+    }
+
+    #[test]
+    fn test_summarize() {
+        let helsinki = chrono_tz::Europe::Helsinki;
+        let parser = Parser::new(helsinki.with_ymd_and_hms(2023, 3, 25, 10, 52, 1).unwrap());
+        let rows = [
+            RowBuilder::new().id("1").build(),
+            RowBuilder::new().id("2").no_price().build(),
+            RowBuilder::new()
+                .id("3")
+                .company_ad("1")
+                .cat_geo(&["Espoo", "Myydään", "Huonekalu Oy"])
+                .build(),
+            RowBuilder::new()
+                .id("4")
+                .price("Sop.")
+                .cat_geo(&["Espoo", "Ostetaan"])
+                .build(),
+            RowBuilder::new()
+                .id("5")
+                .cat_geo(&["Espoo", "Annetaan"])
+                .build(),
+        ];
+        let items = parser.parse_from_string(&rows.concat()).unwrap();
+
+        assert_eq!(
+            summarize(&items),
+            ScrapeSummary {
+                total: 5,
+                with_price: 3,
+                company_ads: 1,
+                private_ads: 4,
+                selling: 3,
+                buying: 1,
+            }
+        );
+        assert_eq!(summarize(&[]), ScrapeSummary::default());
+    }
+}
@@ -1,48 +1,205 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+
 use clap::{self, builder::PossibleValue, value_parser, ValueHint};
+use serde_json::{json, Value};
+use tori_scrape::parse_search_url;
 
-struct App {}
+/// Overrides the directory holding the saved searches.
+const CONFIG_DIR_ENV: &str = "TORI_CONFIG_DIR";
+const STORE_FILE: &str = "searches.json";
 
-impl App {
-    fn new() -> Self {
-        Self {}
+/// Directory for the saved searches: `$TORI_CONFIG_DIR`, or `tori` under the user's config
+/// directory.
+fn config_dir() -> PathBuf {
+    env::var_os(CONFIG_DIR_ENV)
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("XDG_CONFIG_HOME").map(|d| PathBuf::from(d).join("tori")))
+        .or_else(|| env::var_os("HOME").map(|d| PathBuf::from(d).join(".config").join("tori")))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+struct Search {
+    id: usize,
+    url: String,
+    active: bool,
+}
+
+/// Saved searches, stored as a JSON array in a single file.
+struct Store {
+    path: PathBuf,
+    searches: Vec<Search>,
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+impl Store {
+    /// Load the store from `path`. A missing file is an empty store.
+    fn load(path: &Path) -> io::Result<Self> {
+        let mut store = Store {
+            path: path.to_path_buf(),
+            searches: vec![],
+        };
+        if !path.exists() {
+            return Ok(store);
+        }
+
+        let json: Value = serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| invalid_data(format!("{}: {}", path.display(), e)))?;
+        for entry in json.as_array().into_iter().flatten() {
+            let id = entry["id"].as_u64();
+            let url = entry["url"].as_str();
+            match (id, url) {
+                (Some(id), Some(url)) => store.searches.push(Search {
+                    id: id as usize,
+                    url: url.to_string(),
+                    active: entry["active"].as_bool().unwrap_or(true),
+                }),
+                _ => {
+                    return Err(invalid_data(format!(
+                        "{}: bad entry {}",
+                        path.display(),
+                        entry
+                    )))
+                }
+            }
+        }
+        Ok(store)
     }
 
-    fn list(&self, only: Option<String>) {
-        println!("in App::list");
-        if only.is_none() {
-            return;
+    fn save(&self) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
         }
-        let only = only.unwrap();
-        match only.as_str() {
-            "active" => println!(">> only listing active"),
-            "inactive" => println!(">> only listing inactive"),
-            _ => println!("OMG en ymmärtänyt: '{}'", only),
+        let json: Vec<Value> = self
+            .searches
+            .iter()
+            .map(|s| json!({"id": s.id, "url": s.url, "active": s.active}))
+            .collect();
+        let buf = serde_json::to_string_pretty(&json).map_err(|e| invalid_data(e.to_string()))?;
+        fs::write(&self.path, buf)
+    }
+
+    /// Add a search, returning its id. Ids are never reused.
+    fn add(&mut self, url: &str) -> usize {
+        let id = self.searches.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+        self.searches.push(Search {
+            id,
+            url: url.to_string(),
+            active: true,
+        });
+        id
+    }
+
+    fn get(&self, id: usize) -> Option<&Search> {
+        self.searches.iter().find(|s| s.id == id)
+    }
+
+    fn get_mut(&mut self, id: usize) -> Option<&mut Search> {
+        self.searches.iter_mut().find(|s| s.id == id)
+    }
+}
+
+struct App {
+    store: Store,
+}
+
+impl App {
+    fn new(store: Store) -> Self {
+        Self { store }
+    }
+
+    fn list(&self, only: Option<String>) -> Result<(), String> {
+        let wanted = |s: &Search| match only.as_deref() {
+            Some("active") => s.active,
+            Some("inactive") => !s.active,
+            _ => true,
+        };
+        for search in self.store.searches.iter().filter(|s| wanted(s)) {
+            let state = if search.active { "" } else { " (inactive)" };
+            println!("{}\t{}{}", search.id, search.url, state);
         }
+        Ok(())
     }
-    fn register(&self, url: &str) {
-        println!("in App::register '{}'", url);
+
+    fn register(&mut self, url: &str) -> Result<(), String> {
+        parse_search_url(url).map_err(|e| e.to_string())?;
+        let id = self.store.add(url);
+        self.store.save().map_err(|e| e.to_string())?;
+        println!("registered search {}", id);
+        Ok(())
+    }
+
+    /// Mark a search inactive. It stays in the store and in `list`, but is flagged as such.
+    fn deactivate(&mut self, id: usize) -> Result<(), String> {
+        let search = self
+            .store
+            .get_mut(id)
+            .ok_or_else(|| format!("no search with id {}", id))?;
+        search.active = false;
+        self.store.save().map_err(|e| e.to_string())?;
+        println!("deactivated search {}", id);
+        Ok(())
     }
-    fn show(&self, id: usize) {
-        println!("in App::show id={}", id);
+
+    fn show(&self, id: usize) -> Result<(), String> {
+        let search = self
+            .store
+            .get(id)
+            .ok_or_else(|| format!("no search with id {}", id))?;
+        println!("id: {}", search.id);
+        println!("url: {}", search.url);
+        println!("active: {}", search.active);
+
+        // the url was validated on registration, but the store may have been edited by hand.
+        if let Ok(query) = parse_search_url(&search.url) {
+            let fields = [
+                ("region", query.region),
+                ("keywords", query.keywords),
+                ("category", query.category.map(|c| c.to_string())),
+                ("subcategory", query.subcategory.map(|c| c.to_string())),
+                ("area", query.area.map(|a| a.to_string())),
+                ("scope", query.scope.map(|w| w.to_string())),
+            ];
+            for (name, value) in fields {
+                if let Some(value) = value {
+                    println!("{}: {}", name, value);
+                }
+            }
+            if !query.listing_types.is_empty() {
+                println!("listing types: {}", query.listing_types.join(","));
+            }
+        }
+        Ok(())
     }
 }
 
 fn main() {
-    let matches = clap::Command::new("Clap clap")
+    let matches = clap::Command::new("tori")
         .version("0.0.1")
         .subcommand_required(true)
         .subcommand(
-            clap::Command::new("list").about("List all claps").arg(
-                clap::Arg::new("only")
-                    .long("only")
-                    .action(clap::ArgAction::Set)
-                    .value_parser([PossibleValue::new("active"), PossibleValue::new("inactive")])
-                    .required(false),
-            ),
+            clap::Command::new("list")
+                .about("List registered searches")
+                .arg(
+                    clap::Arg::new("only")
+                        .long("only")
+                        .action(clap::ArgAction::Set)
+                        .value_parser([
+                            PossibleValue::new("active"),
+                            PossibleValue::new("inactive"),
+                        ])
+                        .required(false),
+                ),
         )
         .subcommand(
             clap::Command::new("register")
-                .about("Register new clap")
+                .about("Register a search URL")
                 .arg(
                     clap::Arg::new("url")
                         .action(clap::ArgAction::Set)
@@ -52,7 +209,17 @@ fn main() {
         )
         .subcommand(
             clap::Command::new("show")
-                .about("Show details about registered query")
+                .about("Show details about a registered search")
+                .arg(
+                    clap::Arg::new("id")
+                        .action(clap::ArgAction::Set)
+                        .value_parser(value_parser!(usize))
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            clap::Command::new("deactivate")
+                .about("Mark a registered search inactive")
                 .arg(
                     clap::Arg::new("id")
                         .action(clap::ArgAction::Set)
@@ -62,25 +229,25 @@ fn main() {
         )
         .get_matches();
 
-    let app = App::new();
+    let store = match Store::load(&config_dir().join(STORE_FILE)) {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("could not load saved searches: {}", e);
+            process::exit(1);
+        }
+    };
+    let mut app = App::new(store);
 
-    /*
-    if let Some(_sub_matches) = matches.subcommand_matches("list") {
-        return;
-    }
-    if let Some(sub_matches) = matches.subcommand_matches("register") {
-        println!(
-            "register '{}'",
-            sub_matches.get_one::<String>("url").expect("huh")
-        );
-        return;
-    }
-    */
-    match matches.subcommand() {
+    let result = match matches.subcommand() {
         Some(("list", subm)) => app.list(subm.get_one::<String>("only").cloned()),
         Some(("register", subm)) => app.register(subm.get_one::<String>("url").unwrap()),
         Some(("show", subm)) => app.show(*subm.get_one::<usize>("id").unwrap()),
+        Some(("deactivate", subm)) => app.deactivate(*subm.get_one::<usize>("id").unwrap()),
         Some((_, _)) => panic!("unknown subcommand"),
         None => panic!("should not get here"),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
     }
 }
@@ -0,0 +1,158 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+
+use crate::parsing::{Item, ItemParseError, Parser};
+
+/// Wraps a [Parser] and remembers the items parsed from each input buffer, so that parsing
+/// an unchanged page again returns the cached result instead of reparsing it. Buffers are
+/// looked up by their hash, and the cached buffer is compared on every hit so that a hash
+/// collision is never mistaken for a match. Only successful parses are cached.
+pub struct CachingParser {
+    parser: Parser,
+    // the input is kept next to its items to tell hash collisions apart from real hits.
+    cache: HashMap<u64, (String, Vec<Item>)>,
+    // insertion order, for evicting the oldest entry once the cache is full.
+    order: VecDeque<u64>,
+    capacity: usize,
+    hits: usize,
+}
+
+impl CachingParser {
+    /// Wrap `parser` with an unbounded cache.
+    pub fn new(parser: Parser) -> Self {
+        Self::with_capacity(parser, usize::MAX)
+    }
+
+    /// Wrap `parser` with a cache holding at most `capacity` documents. The oldest entry is
+    /// evicted first.
+    pub fn with_capacity(parser: Parser, capacity: usize) -> Self {
+        CachingParser {
+            parser,
+            cache: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+            hits: 0,
+        }
+    }
+
+    /// Like [Parser::parse_from_string], but returns a copy of the cached items if the same
+    /// buffer has been parsed before.
+    pub fn parse_from_string(&mut self, buf: &str) -> Result<Vec<Item>, ItemParseError> {
+        let mut hasher = DefaultHasher::new();
+        buf.hash(&mut hasher);
+        let key = hasher.finish();
+
+        if let Some((cached_buf, items)) = self.cache.get(&key) {
+            if cached_buf == buf {
+                self.hits += 1;
+                return Ok(items.clone());
+            }
+        }
+
+        let items = self.parser.parse_from_string(buf)?;
+        if self.capacity > 0 {
+            // on a collision the new buffer replaces the old one in the same slot
+            if !self.cache.contains_key(&key) {
+                if self.order.len() >= self.capacity {
+                    if let Some(oldest) = self.order.pop_front() {
+                        self.cache.remove(&oldest);
+                    }
+                }
+                self.order.push_back(key);
+            }
+            self.cache.insert(key, (buf.to_string(), items.clone()));
+        }
+        Ok(items)
+    }
+
+    /// Number of calls answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Number of documents currently cached.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// The wrapped parser.
+    pub fn parser(&self) -> &Parser {
+        &self.parser
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::RowBuilder;
+    use chrono::TimeZone;
+
+    fn parser() -> Parser {
+        let helsinki = chrono_tz::Europe::Helsinki;
+        Parser::new(helsinki.with_ymd_and_hms(2023, 3, 25, 10, 52, 1).unwrap())
+    }
+
+    #[test]
+    fn test_cache_hit() {
+        let mut parser = CachingParser::new(parser());
+        let buf = RowBuilder::new().id("1").build();
+
+        let first = parser.parse_from_string(&buf).unwrap();
+        assert_eq!(parser.hits(), 0);
+        let second = parser.parse_from_string(&buf).unwrap();
+        assert_eq!(parser.hits(), 1);
+        assert_eq!(first[0].item_id, second[0].item_id);
+
+        parser
+            .parse_from_string(&RowBuilder::new().id("2").build())
+            .unwrap();
+        assert_eq!(parser.hits(), 1);
+        assert_eq!(parser.len(), 2);
+    }
+
+    #[test]
+    fn test_cache_collision() {
+        let mut parser = CachingParser::new(parser());
+        let first = RowBuilder::new().id("1").build();
+        let second = RowBuilder::new().id("2").build();
+
+        parser.parse_from_string(&first).unwrap();
+        // pretend the second document hashes to the same key as the first
+        let key = *parser.cache.keys().next().unwrap();
+        let second_items = parser.parser().parse_from_string(&second).unwrap();
+        parser.cache.insert(key, (second, second_items));
+
+        let items = parser.parse_from_string(&first).unwrap();
+        assert_eq!(parser.hits(), 0);
+        assert_eq!(items[0].item_id, "1");
+        assert_eq!(parser.len(), 1);
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Parser>();
+        assert_send_sync::<CachingParser>();
+    }
+
+    #[test]
+    fn test_cache_capacity() {
+        let mut parser = CachingParser::with_capacity(parser(), 1);
+        let first = RowBuilder::new().id("1").build();
+
+        parser.parse_from_string(&first).unwrap();
+        parser
+            .parse_from_string(&RowBuilder::new().id("2").build())
+            .unwrap();
+        assert_eq!(parser.len(), 1);
+
+        // evicted by the second document
+        parser.parse_from_string(&first).unwrap();
+        assert_eq!(parser.hits(), 0);
+    }
+}
@@ -0,0 +1,155 @@
+//! Building blocks of the timestamp parser that need neither regular expressions nor the HTML
+//! parser. This module only depends on `chrono` and [locale](crate::locale), so it is
+//! available even when the crate is built without the default `html` feature.
+//! [DateParser](crate::DateParser) is built on top of these.
+
+use chrono::{Month, NaiveTime};
+
+use crate::locale::Locale;
+
+#[derive(Debug, PartialEq)]
+pub enum DateParseError {
+    InvalidHighlevelStructure(String),
+    /// The day is out of range, or does not exist in the month (and year) of the timestamp.
+    /// Holds the day as written, e.g. `"31"` for `31 hel 12:00`.
+    InvalidDay(String),
+    InvalidTime(String),
+    InvalidMonth(String),
+    InvalidRelativeDay(String),
+    /// The local time does not exist in the user's timezone, e.g. it falls inside the gap
+    /// of a daylight saving time transition.
+    NonexistentLocalTime(String),
+    ArithmeticProblem,
+}
+
+impl DateParseError {
+    /// Stable snake_case code of the error kind, e.g. `"invalid_day"`. Suitable for logs and
+    /// metrics; unlike the `Debug` output, the codes will not change.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DateParseError::InvalidHighlevelStructure(_) => "invalid_structure",
+            DateParseError::InvalidDay(_) => "invalid_day",
+            DateParseError::InvalidTime(_) => "invalid_time",
+            DateParseError::InvalidMonth(_) => "invalid_month",
+            DateParseError::InvalidRelativeDay(_) => "invalid_relative_day",
+            DateParseError::NonexistentLocalTime(_) => "nonexistent_local_time",
+            DateParseError::ArithmeticProblem => "arithmetic",
+        }
+    }
+
+    /// The offending input, if the error carries one.
+    pub fn value(&self) -> Option<&str> {
+        match self {
+            DateParseError::InvalidHighlevelStructure(s)
+            | DateParseError::InvalidDay(s)
+            | DateParseError::InvalidTime(s)
+            | DateParseError::InvalidMonth(s)
+            | DateParseError::InvalidRelativeDay(s)
+            | DateParseError::NonexistentLocalTime(s) => Some(s),
+            DateParseError::ArithmeticProblem => None,
+        }
+    }
+}
+
+pub type DateParseResult<T> = Result<T, DateParseError>;
+
+/// Parses a time of day such as `19:52`.
+pub fn parse_hh_mm(time: &str) -> DateParseResult<NaiveTime> {
+    NaiveTime::parse_from_str(time, "%H:%M")
+        .map_err(|_| DateParseError::InvalidTime(time.to_string()))
+}
+
+/// Parses a day of month. Only the generic 1-31 range is checked, as the month is not known
+/// here.
+pub fn parse_day(day: &str) -> DateParseResult<u32> {
+    match day.parse::<u32>() {
+        Ok(d) if (1..=31).contains(&d) => Ok(d),
+        _ => Err(DateParseError::InvalidDay(day.to_string())),
+    }
+}
+
+/// Month from its abbreviation in the given locale, e.g. `huh` in [Finnish](crate::locale::Finnish).
+pub fn parse_month_short(locale: &dyn Locale, month_short_name: &str) -> DateParseResult<Month> {
+    locale
+        .month_from_short(month_short_name)
+        .ok_or_else(|| DateParseError::InvalidMonth(month_short_name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::{Finnish, Swedish};
+
+    #[test]
+    fn test_date_parse_error_code() {
+        let s = || "x".to_string();
+        let errors = [
+            (
+                DateParseError::InvalidHighlevelStructure(s()),
+                "invalid_structure",
+            ),
+            (DateParseError::InvalidDay(s()), "invalid_day"),
+            (DateParseError::InvalidTime(s()), "invalid_time"),
+            (DateParseError::InvalidMonth(s()), "invalid_month"),
+            (
+                DateParseError::InvalidRelativeDay(s()),
+                "invalid_relative_day",
+            ),
+            (
+                DateParseError::NonexistentLocalTime(s()),
+                "nonexistent_local_time",
+            ),
+        ];
+        for (error, code) in errors {
+            assert_eq!(error.code(), code);
+            assert_eq!(error.value(), Some("x"));
+        }
+
+        assert_eq!(DateParseError::ArithmeticProblem.code(), "arithmetic");
+        assert_eq!(DateParseError::ArithmeticProblem.value(), None);
+    }
+
+    #[test]
+    fn test_parse_hh_mm() {
+        assert_eq!(
+            parse_hh_mm("01:23"),
+            Ok(NaiveTime::from_hms_opt(1, 23, 0).unwrap())
+        );
+        assert_eq!(
+            parse_hh_mm("01:60"),
+            Err(DateParseError::InvalidTime("01:60".to_string()))
+        );
+        assert_eq!(
+            parse_hh_mm("25:24"),
+            Err(DateParseError::InvalidTime("25:24".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_day() {
+        assert_eq!(parse_day("1"), Ok(1));
+        assert_eq!(parse_day("31"), Ok(31));
+        assert_eq!(
+            parse_day("0"),
+            Err(DateParseError::InvalidDay("0".to_string()))
+        );
+        assert_eq!(
+            parse_day("32"),
+            Err(DateParseError::InvalidDay("32".to_string()))
+        );
+        assert_eq!(
+            parse_day("x"),
+            Err(DateParseError::InvalidDay("x".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_month_short() {
+        assert_eq!(parse_month_short(&Finnish, "huh"), Ok(Month::April));
+        assert_eq!(parse_month_short(&Swedish, "apr"), Ok(Month::April));
+        assert_eq!(
+            parse_month_short(&Finnish, "apr"),
+            Err(DateParseError::InvalidMonth("apr".to_string()))
+        );
+    }
+}
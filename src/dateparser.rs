@@ -0,0 +1,655 @@
+use chrono::{DateTime, Datelike, Days, Duration, LocalResult, Month, TimeZone, Utc};
+use chrono::{NaiveDate, NaiveDateTime};
+use chrono_tz::Tz;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::datecore::{self, parse_day, parse_hh_mm};
+pub use crate::datecore::{DateParseError, DateParseResult};
+use crate::locale::{Finnish, Locale, RelDay, RelativeDayWords};
+
+/// Parses the timestamps shown in tori.fi listings into UTC. Most timestamps lack the year
+/// and many are relative to the current day, so they are resolved against the time the page
+/// was fetched, as seen in the user's timezone. This is the standalone date API, usable
+/// without a full [Parser](crate::Parser). Month and day names are interpreted according to
+/// a [Locale], Finnish by default.
+///
+/// # Examples
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tori_scrape::DateParser;
+///
+/// let server_time = Utc.with_ymd_and_hms(2023, 3, 25, 8, 52, 1).unwrap();
+/// let parser = DateParser::new(server_time, chrono_tz::Europe::Helsinki);
+///
+/// let posted_at = parser.parse("21 huh 19:52").unwrap();
+/// assert_eq!(posted_at, Utc.with_ymd_and_hms(2022, 4, 21, 16, 52, 0).unwrap());
+/// ```
+pub struct DateParser {
+    server_time: DateTime<Utc>,
+    user_today: DateTime<Tz>,
+    user_yesterday: DateTime<Tz>,
+    locale: Box<dyn Locale>,
+    // words added with with_relative_day_words, kept so that a later with_locale can wrap the
+    // new locale with them too.
+    relative_day_words: Vec<(String, String)>,
+}
+
+lazy_static! {
+    // the words are recognized by the Locale; these only capture the overall structure.
+    // the month is any word, so that a bad month in an otherwise well-formed timestamp is
+    // reported as InvalidMonth regardless of its length.
+    static ref REL_TIME: Regex = Regex::new(r"\s*\b(\p{L}+)\s+(\d{2}:\d{2})\s*").unwrap();
+    static ref ABS_TIME: Regex =
+        Regex::new(r"\s*(\d{1,2})\s+(\p{L}+)\s+(\d{2}:\d{2})\s*").unwrap();
+    // older listings may include the year, e.g. "21 huh 2021 19:52". `\d` would also match
+    // non-ASCII digits, which str::parse rejects, so the year is matched as ASCII only.
+    static ref ABS_TIME_YEAR: Regex =
+        Regex::new(r"\s*(\d{1,2})\s+(\p{L}+)\s+([0-9]{4})\s+(\d{2}:\d{2})\s*").unwrap();
+    // the newest listings on some layouts, e.g. "5 min sitten". these are Finnish only.
+    static ref AGO_TIME: Regex = Regex::new(
+        r"\s*(\d+)\s+(min|minuutti|minuuttia|t|tunti|tuntia)\s+sitten\s*"
+    )
+    .unwrap();
+}
+
+/// Whether `ts` has the structure of a timestamp relative to the fetch time, such as
+/// `tänään 12:34` or `5 min sitten`. The relative day word is not validated against any
+/// locale.
+#[cfg(feature = "html")]
+pub(crate) fn is_relative(ts: &str) -> bool {
+    AGO_TIME.is_match(ts)
+        || (!ABS_TIME.is_match(ts) && !ABS_TIME_YEAR.is_match(ts) && REL_TIME.is_match(ts))
+}
+
+impl DateParser {
+    /// Construct new DateParser. `server_time` is the instant the page was fetched, and
+    /// `user_tz` the timezone in which the page renders its timestamps.
+    pub fn new(server_time: DateTime<Utc>, user_tz: Tz) -> Self {
+        let user_today = server_time.with_timezone(&user_tz);
+        DateParser {
+            server_time: server_time,
+            user_today: user_today,
+            user_yesterday: user_today - Days::new(1),
+            locale: Box::new(Finnish),
+            relative_day_words: vec![],
+        }
+    }
+
+    /// Recognize `today` and `yesterday` as the relative day words, in addition to those of
+    /// the locale. The words are kept across [with_locale](DateParser::with_locale), so the
+    /// order of the two calls does not matter.
+    pub fn with_relative_day_words(mut self, today: &str, yesterday: &str) -> Self {
+        self.locale = Box::new(RelativeDayWords::new(today, yesterday, self.locale));
+        self.relative_day_words
+            .push((today.to_string(), yesterday.to_string()));
+        self
+    }
+
+    /// The fetch time relative timestamps are resolved against, in the user's timezone.
+    pub fn fetch_time(&self) -> DateTime<Tz> {
+        self.user_today
+    }
+
+    /// The instant the page was fetched, as given to [new](DateParser::new). Timestamps
+    /// are compared against this to tell whether they are in the future.
+    pub fn server_time(&self) -> DateTime<Utc> {
+        self.server_time
+    }
+
+    /// The timezone in which timestamps are interpreted.
+    pub fn timezone(&self) -> Tz {
+        self.user_today.timezone()
+    }
+
+    /// Use the given locale for interpreting month and day names instead of [Finnish]. Words
+    /// added with [with_relative_day_words](DateParser::with_relative_day_words) remain
+    /// recognized.
+    pub fn with_locale(mut self, locale: Box<dyn Locale>) -> Self {
+        self.locale = self
+            .relative_day_words
+            .iter()
+            .fold(locale, |base, (today, yesterday)| {
+                Box::new(RelativeDayWords::new(today, yesterday, base))
+            });
+        self
+    }
+
+    fn parse_month_short(&self, month_short_name: &str) -> DateParseResult<Month> {
+        datecore::parse_month_short(self.locale.as_ref(), month_short_name)
+    }
+
+    fn parse_relative_day(&self, relday_s: &str) -> DateParseResult<RelDay> {
+        self.locale
+            .relative_day(relday_s)
+            .ok_or(DateParseError::InvalidRelativeDay(relday_s.to_string()))
+    }
+
+    fn parse_rel_time(&self, relday_s: &str, hhmm_s: &str) -> DateParseResult<DateTime<Utc>> {
+        let naive_time = parse_hh_mm(hhmm_s)?;
+
+        let naive_date = match self.parse_relative_day(relday_s)? {
+            RelDay::Today => Ok(self.user_today.date_naive()),
+            RelDay::Yesterday => Ok(self.user_yesterday.date_naive()),
+            // weekday names refer to the most recent such day, today included.
+            RelDay::Weekday(weekday) => {
+                let today = self.user_today.date_naive();
+                let days_back = (7 + today.weekday().num_days_from_monday()
+                    - weekday.num_days_from_monday())
+                    % 7;
+                today
+                    .checked_sub_days(Days::new(days_back as u64))
+                    .ok_or(DateParseError::ArithmeticProblem)
+            }
+        }?;
+
+        let date = NaiveDateTime::new(naive_date, naive_time);
+        Ok(self.localize(&date)?.with_timezone(&Utc))
+    }
+
+    /// Interpret the naive timestamp in the user's timezone.
+    fn localize(&self, date: &NaiveDateTime) -> DateParseResult<DateTime<Tz>> {
+        match self.user_today.timezone().from_local_datetime(date) {
+            LocalResult::Single(new_ts) => Ok(new_ts),
+            LocalResult::None => Err(DateParseError::NonexistentLocalTime(date.to_string())),
+            LocalResult::Ambiguous(..) => Err(DateParseError::ArithmeticProblem),
+        }
+    }
+
+    fn parse_abs_time(
+        &self,
+        day_s: &str,
+        month_s: &str,
+        hhmm_s: &str,
+    ) -> DateParseResult<DateTime<Utc>> {
+        let day = parse_day(day_s)?;
+        let month = self.parse_month_short(month_s)?;
+        let naive_time = parse_hh_mm(hhmm_s)?;
+
+        // timestamp lacks the year; pick the most recent year in which the date exists and
+        // is not in the future. this assumes no item can be listed for over a year. note that
+        // parse_day() only checks the generic 1-31 range, so days that do not exist in the
+        // given month (e.g. "31 hel", or "29 hel" outside leap years) are rejected here.
+        let this_year = self.user_today.year();
+        for year in [this_year, this_year - 1] {
+            let naive_date = match NaiveDate::from_ymd_opt(year, month.number_from_month(), day) {
+                Some(naive_date) => naive_date,
+                None => continue,
+            };
+
+            let naive_ts = naive_date.and_time(naive_time);
+            let new_ts = match self.localize(&naive_ts) {
+                Ok(new_ts) => new_ts.with_timezone(&Utc),
+                // a candidate in the DST gap that is in the future would be rejected anyway,
+                // so try the previous year instead of failing.
+                Err(DateParseError::NonexistentLocalTime(_))
+                    if naive_ts > self.user_today.naive_local() =>
+                {
+                    continue
+                }
+                Err(e) => return Err(e),
+            };
+
+            // the year is picked by the local calendar, but "in the future" is decided by
+            // comparing UTC instants so that no local offset can skew the result.
+            if new_ts <= self.server_time {
+                return Ok(new_ts);
+            }
+        }
+
+        Err(DateParseError::InvalidDay(day_s.to_string()))
+    }
+
+    /// Resolves `5 min sitten` or `2 t sitten` by subtracting from the fetch time.
+    fn parse_ago_time(&self, amount_s: &str, unit_s: &str) -> DateParseResult<DateTime<Utc>> {
+        let amount = amount_s
+            .parse::<u32>()
+            .map_err(|_| DateParseError::InvalidTime(format!("{} {}", amount_s, unit_s)))?;
+        let ago = match unit_s {
+            "min" | "minuutti" | "minuuttia" => Duration::minutes(amount as i64),
+            _ => Duration::hours(amount as i64),
+        };
+        self.server_time
+            .checked_sub_signed(ago)
+            .ok_or(DateParseError::ArithmeticProblem)
+    }
+
+    /// Like [parse_abs_time](DateParser::parse_abs_time), but the year is given, so it is
+    /// used as is instead of guessing it from the fetch time.
+    fn parse_abs_time_with_year(
+        &self,
+        day_s: &str,
+        month_s: &str,
+        year_s: &str,
+        hhmm_s: &str,
+    ) -> DateParseResult<DateTime<Utc>> {
+        let day = parse_day(day_s)?;
+        let month = self.parse_month_short(month_s)?;
+        let naive_time = parse_hh_mm(hhmm_s)?;
+
+        // an unusable year is reported like a date that does not exist.
+        let year = year_s
+            .parse::<i32>()
+            .map_err(|_| DateParseError::InvalidDay(day_s.to_string()))?;
+        let naive_date = NaiveDate::from_ymd_opt(year, month.number_from_month(), day)
+            .ok_or_else(|| DateParseError::InvalidDay(day_s.to_string()))?;
+
+        Ok(self
+            .localize(&naive_date.and_time(naive_time))?
+            .with_timezone(&Utc))
+    }
+
+    /// Parse timestamp of an item listing (when it was posted). The input string can contain
+    /// either 1) _absolute_ timestamp such as `15 huh 12:45` or 2) _relative_ timestamp such
+    /// as `tänään 12:34`, `eilen 12:34` or `ma 12:34` (the most recent monday). The examples
+    /// are in the default [Finnish] locale; see [with_locale](DateParser::with_locale).
+    /// Absolute timestamps may also include the year (`21 huh 2021 19:52`); otherwise the
+    /// most recent year in which the date is not in the future is assumed. The newest
+    /// listings may instead show the time elapsed since posting, in minutes (`5 min sitten`)
+    /// or hours (`2 t sitten`); these are subtracted from the fetch time.
+    ///
+    /// Relative timestamps are always placed on the day they name, even when that is later
+    /// than the fetch time, e.g. `tänään 23:50` on a page fetched at 00:10. Such a timestamp
+    /// means the fetch time or the site's clock is off; moving it to another day would only
+    /// hide that. `Item::validate` reports these as `ItemWarning::PostedInFuture`.
+    pub fn parse(&self, ts: &str) -> DateParseResult<DateTime<Utc>> {
+        // the relative pattern would also match the tail of an absolute timestamp
+        // (`huh 19:52`), so the absolute ones must be tried first.
+        if let Some(patts) = ABS_TIME_YEAR.captures(ts) {
+            let (_, [day_s, month_s, year_s, hhmm_s]) = patts.extract();
+            self.parse_abs_time_with_year(day_s, month_s, year_s, hhmm_s)
+        } else if let Some(patts) = ABS_TIME.captures(ts) {
+            let (_, [day_s, month_s, hhmm_s]) = patts.extract();
+            self.parse_abs_time(day_s, month_s, hhmm_s)
+        } else if let Some(patts) = REL_TIME.captures(ts) {
+            let (_, [relday_s, hhmm_s]) = patts.extract();
+            self.parse_rel_time(relday_s, hhmm_s)
+        } else if let Some(patts) = AGO_TIME.captures(ts) {
+            let (_, [amount_s, unit_s]) = patts.extract();
+            self.parse_ago_time(amount_s, unit_s)
+        } else {
+            Err(DateParseError::InvalidHighlevelStructure(ts.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::Swedish;
+
+    fn get_time() -> DateTime<Tz> {
+        chrono_tz::Europe::Helsinki
+            .with_ymd_and_hms(2023, 3, 25, 10, 52, 1)
+            .unwrap()
+    }
+
+    fn parser_at(fetch_time: DateTime<Tz>) -> DateParser {
+        DateParser::new(fetch_time.with_timezone(&Utc), fetch_time.timezone())
+    }
+
+    #[test]
+    fn test_parse_month_short() {
+        let parser = parser_at(get_time());
+        assert_eq!(parser.parse_month_short("tam"), Ok(Month::January));
+        assert_eq!(
+            parser.parse_month_short("foo"),
+            Err(DateParseError::InvalidMonth("foo".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_month_abbrevs() {
+        use crate::locale::{FINNISH_MONTH_ABBREVS, SWEDISH_MONTH_ABBREVS};
+
+        assert_eq!(FINNISH_MONTH_ABBREVS.len(), 12);
+        let parser = parser_at(get_time());
+        for (abbrev, month) in FINNISH_MONTH_ABBREVS {
+            assert_eq!(parser.parse_month_short(abbrev), Ok(month));
+            assert_eq!(parser.parse_month_short(&abbrev.to_uppercase()), Ok(month));
+        }
+
+        assert_eq!(SWEDISH_MONTH_ABBREVS.len(), 12);
+        let parser = parser_at(get_time()).with_locale(Box::new(Swedish));
+        for (abbrev, month) in SWEDISH_MONTH_ABBREVS {
+            assert_eq!(parser.parse_month_short(abbrev), Ok(month));
+        }
+    }
+
+    #[test]
+    fn test_parse_ts_relative_swedish() {
+        let parser = parser_at(get_time());
+
+        assert_eq!(
+            parser.parse("idag 09:15"),
+            Ok(chrono_tz::Europe::Helsinki
+                .with_ymd_and_hms(2023, 3, 25, 9, 15, 0)
+                .unwrap()
+                .with_timezone(&Utc))
+        );
+        assert_eq!(
+            parser.parse("igår 23:59"),
+            Ok(chrono_tz::Europe::Helsinki
+                .with_ymd_and_hms(2023, 3, 24, 23, 59, 0)
+                .unwrap()
+                .with_timezone(&Utc))
+        );
+    }
+
+    #[test]
+    fn test_parse_ts_relative_swedish_locale() {
+        let parser = parser_at(get_time()).with_locale(Box::new(Swedish));
+        assert_eq!(
+            parser.parse("idag 09:15"),
+            parser_at(get_time()).parse("idag 09:15")
+        );
+        assert!(parser.parse("lör 09:15").is_ok());
+    }
+
+    #[test]
+    fn test_relative_day_words_with_locale() {
+        let expected = parser_at(get_time()).parse("idag 09:15");
+        assert!(expected.is_ok());
+
+        for parser in [
+            parser_at(get_time())
+                .with_relative_day_words("today", "yesterday")
+                .with_locale(Box::new(Swedish)),
+            parser_at(get_time())
+                .with_locale(Box::new(Swedish))
+                .with_relative_day_words("today", "yesterday"),
+        ] {
+            assert_eq!(parser.parse("today 09:15"), expected);
+            // the words are added to the locale's own, not replacing them
+            assert_eq!(parser.parse("idag 09:15"), expected);
+            assert!(parser.parse("lör 09:15").is_ok());
+            // the previous locale is gone
+            assert!(parser.parse("tänään 09:15").is_err());
+        }
+    }
+
+    #[test]
+    fn test_date_parser_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<DateParser>();
+    }
+
+    #[test]
+    fn test_parse_ts_custom_locale() {
+        struct Estonian;
+
+        impl Locale for Estonian {
+            fn month_from_short(&self, s: &str) -> Option<Month> {
+                match s {
+                    "mär" => Some(Month::March),
+                    _ => None,
+                }
+            }
+            fn relative_day(&self, s: &str) -> Option<RelDay> {
+                match s {
+                    "täna" => Some(RelDay::Today),
+                    _ => None,
+                }
+            }
+        }
+
+        let parser = parser_at(get_time()).with_locale(Box::new(Estonian));
+        assert_eq!(
+            parser.parse("21 mär 10:00"),
+            Ok(chrono_tz::Europe::Helsinki
+                .with_ymd_and_hms(2023, 3, 21, 10, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc))
+        );
+        assert_eq!(
+            parser.parse("täna 09:00"),
+            Ok(chrono_tz::Europe::Helsinki
+                .with_ymd_and_hms(2023, 3, 25, 9, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc))
+        );
+        assert_eq!(
+            parser.parse("21 huh 10:00"),
+            Err(DateParseError::InvalidMonth("huh".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_ts_relative_weekday() {
+        // get_time() is a saturday
+        let parser = parser_at(get_time());
+
+        let test_data = vec![
+            ("ma", 20),
+            ("ti", 21),
+            ("ke", 22),
+            ("to", 23),
+            ("pe", 24),
+            ("la", 25),
+            ("su", 19),
+        ];
+
+        for (weekday, day) in test_data {
+            let result = parser.parse(&format!("{} 14:30", weekday));
+            assert_eq!(
+                result,
+                Ok(chrono_tz::Europe::Helsinki
+                    .with_ymd_and_hms(2023, 3, day, 14, 30, 0)
+                    .unwrap()
+                    .with_timezone(&Utc)),
+                "weekday: {}",
+                weekday
+            );
+        }
+
+        assert_eq!(
+            parser.parse_relative_day("xx"),
+            Err(DateParseError::InvalidRelativeDay("xx".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_ts_absolute() {
+        let parser = parser_at(get_time());
+        let result = parser.parse("21 huh 19:52");
+        assert_eq!(
+            result,
+            Ok(chrono_tz::Europe::Helsinki
+                .with_ymd_and_hms(2022, 4, 21, 19, 52, 0)
+                .unwrap()
+                .with_timezone(&Utc))
+        );
+        let result = parser.parse("32 tam 01:32");
+        assert_eq!(result, Err(DateParseError::InvalidDay("32".to_string())));
+    }
+
+    #[test]
+    fn test_parse_ts_absolute_invalid_month() {
+        let parser = parser_at(get_time());
+        for month in ["hu", "xyz", "huhti"] {
+            assert_eq!(
+                parser.parse(&format!("21 {} 19:52", month)),
+                Err(DateParseError::InvalidMonth(month.to_string()))
+            );
+        }
+        assert_eq!(
+            parser.parse("21 hu 2021 19:52"),
+            Err(DateParseError::InvalidMonth("hu".to_string()))
+        );
+        #[cfg(feature = "html")]
+        assert!(!is_relative("21 hu 19:52"));
+    }
+
+    #[test]
+    fn test_parse_ts_absolute_with_year() {
+        let parser = parser_at(get_time());
+        assert_eq!(
+            parser.parse("21 huh 2021 19:52"),
+            Ok(chrono_tz::Europe::Helsinki
+                .with_ymd_and_hms(2021, 4, 21, 19, 52, 0)
+                .unwrap()
+                .with_timezone(&Utc))
+        );
+        // without the year, the most recent past april 21st is picked
+        assert_eq!(
+            parser.parse("21 huh 19:52"),
+            Ok(chrono_tz::Europe::Helsinki
+                .with_ymd_and_hms(2022, 4, 21, 19, 52, 0)
+                .unwrap()
+                .with_timezone(&Utc))
+        );
+        #[cfg(feature = "html")]
+        assert!(!is_relative("21 huh 2021 19:52"));
+
+        // 2021 is not a leap year
+        assert_eq!(
+            parser.parse("29 hel 2021 10:00"),
+            Err(DateParseError::InvalidDay("29".to_string()))
+        );
+
+        // non-ASCII digits are not taken as a year
+        assert!(parser.parse("21 huh ٢٠٢١ 19:52").is_err());
+    }
+
+    #[test]
+    fn test_parse_ts_absolute_leap_day() {
+        let helsinki = chrono_tz::Europe::Helsinki;
+        let expected = Ok(helsinki
+            .with_ymd_and_hms(2024, 2, 29, 10, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc));
+
+        // leap day of the current year
+        let parser = parser_at(helsinki.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap());
+        assert_eq!(parser.parse("29 hel 10:00"), expected);
+
+        // current year is not a leap year, but the previous one is
+        let parser = parser_at(helsinki.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap());
+        assert_eq!(parser.parse("29 hel 10:00"), expected);
+
+        // no leap day within the past year: 2024-02-29 would be in the future and
+        // 2023-02-29 does not exist.
+        let parser = parser_at(helsinki.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap());
+        assert_eq!(
+            parser.parse("29 hel 10:00"),
+            Err(DateParseError::InvalidDay("29".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_ts_absolute_nonexistent_day() {
+        let parser = parser_at(get_time());
+        let result = parser.parse("31 hel 10:00");
+        assert_eq!(result, Err(DateParseError::InvalidDay("31".to_string())));
+        // 2023 is not a leap year
+        let result = parser.parse("29 hel 10:00");
+        assert_eq!(result, Err(DateParseError::InvalidDay("29".to_string())));
+        let result = parser.parse("31 huh 10:00");
+        assert_eq!(result, Err(DateParseError::InvalidDay("31".to_string())));
+    }
+
+    #[test]
+    fn test_parse_ts_absolute_around_midnight() {
+        // just past new year in Helsinki, but still 2023 in UTC
+        let helsinki = chrono_tz::Europe::Helsinki;
+        let parser = parser_at(helsinki.with_ymd_and_hms(2024, 1, 1, 0, 30, 0).unwrap());
+
+        assert_eq!(
+            parser.parse("1 tam 00:15"),
+            Ok(Utc.with_ymd_and_hms(2023, 12, 31, 22, 15, 0).unwrap())
+        );
+        assert_eq!(
+            parser.parse("31 jou 23:50"),
+            Ok(Utc.with_ymd_and_hms(2023, 12, 31, 21, 50, 0).unwrap())
+        );
+        // later today would be in the future, so it must be from the previous year
+        assert_eq!(
+            parser.parse("1 tam 00:45"),
+            Ok(Utc.with_ymd_and_hms(2022, 12, 31, 22, 45, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parse_ts_ago() {
+        let parser = parser_at(get_time());
+        let fetch_time = get_time().with_timezone(&Utc);
+
+        assert_eq!(
+            parser.parse("5 min sitten"),
+            Ok(fetch_time - Duration::minutes(5))
+        );
+        assert_eq!(
+            parser.parse("45 minuuttia sitten"),
+            Ok(fetch_time - Duration::minutes(45))
+        );
+        assert_eq!(
+            parser.parse("2 t sitten"),
+            Ok(fetch_time - Duration::hours(2))
+        );
+        assert_eq!(
+            parser.parse(" 1 tunti  sitten "),
+            Ok(fetch_time - Duration::hours(1))
+        );
+        assert_eq!(
+            parser.parse("3 päivää sitten"),
+            Err(DateParseError::InvalidHighlevelStructure(
+                "3 päivää sitten".to_string()
+            ))
+        );
+        #[cfg(feature = "html")]
+        assert!(is_relative("5 min sitten"));
+    }
+
+    #[test]
+    fn test_parse_ts_relative_after_fetch_time() {
+        let helsinki = chrono_tz::Europe::Helsinki;
+        let parser = parser_at(helsinki.with_ymd_and_hms(2023, 3, 25, 0, 10, 0).unwrap());
+
+        assert_eq!(
+            parser.parse("eilen 23:50"),
+            Ok(helsinki
+                .with_ymd_and_hms(2023, 3, 24, 23, 50, 0)
+                .unwrap()
+                .with_timezone(&Utc))
+        );
+        // in the future, but kept on the day it names
+        assert_eq!(
+            parser.parse("tänään 23:50"),
+            Ok(helsinki
+                .with_ymd_and_hms(2023, 3, 25, 23, 50, 0)
+                .unwrap()
+                .with_timezone(&Utc))
+        );
+    }
+
+    #[test]
+    fn test_parse_ts_nonexistent_local_time() {
+        // clocks in Helsinki jump from 03:00 to 04:00 on 2024-03-31
+        let helsinki = chrono_tz::Europe::Helsinki;
+        let parser = parser_at(helsinki.with_ymd_and_hms(2024, 3, 31, 12, 0, 0).unwrap());
+        let expected = Err(DateParseError::NonexistentLocalTime(
+            "2024-03-31 03:30:00".to_string(),
+        ));
+        assert_eq!(parser.parse("tänään 03:30"), expected);
+        assert_eq!(parser.parse("31 maa 03:30"), expected);
+        assert!(parser.parse("tänään 04:30").is_ok());
+
+        // this year's candidate is in the gap, but also in the future, so last year is used
+        let parser = parser_at(helsinki.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap());
+        assert_eq!(
+            parser.parse("31 maa 03:30"),
+            Ok(helsinki
+                .with_ymd_and_hms(2023, 3, 31, 3, 30, 0)
+                .unwrap()
+                .with_timezone(&Utc))
+        );
+    }
+
+    #[test]
+    fn test_parse_day() {
+        assert!(parse_day("0").is_err());
+        assert!(parse_day("32").is_err());
+        assert!(parse_day("1").unwrap() == 1);
+        assert!(parse_day("31").unwrap() == 31);
+    }
+}
@@ -1,4 +1,40 @@
+#[cfg(feature = "html")]
+mod aggregate;
+#[cfg(feature = "html")]
+mod cache;
+pub mod datecore;
+mod dateparser;
+pub mod locale;
+#[cfg(feature = "parquet")]
+mod parquet_export;
+#[cfg(feature = "html")]
 mod parsing;
+mod search;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(all(test, feature = "html"))]
+mod testutil;
 pub mod utils;
 
-pub use parsing::Parser;
+#[cfg(feature = "html")]
+pub use aggregate::{
+    diff, group_by_location, partition_valid, price_stats, summarize, PriceStats, ScrapeDiff,
+    ScrapeSummary,
+};
+#[cfg(feature = "html")]
+pub use cache::CachingParser;
+pub use dateparser::DateParser;
+#[cfg(feature = "parquet")]
+pub use parquet_export::export_to_parquet;
+#[cfg(all(feature = "html", feature = "rust_decimal"))]
+pub use parsing::DecimalPrice;
+#[cfg(feature = "html")]
+pub use parsing::{price_parse, price_parse_opt, price_parse_with};
+#[cfg(feature = "html")]
+pub use parsing::{
+    Direction, Item, ItemAttribute, ItemParseError, ItemParseErrorKind, ItemWarning, NumberFormat,
+    ParseStats, Parser, Price, PriceKind, SelectorCounts, Selectors,
+};
+pub use search::{parse_search_url, SearchQuery, UrlError};
+#[cfg(feature = "sqlite")]
+pub use sqlite::export_to_sqlite;
@@ -0,0 +1,149 @@
+use chrono::{Month, Weekday};
+
+/// Day referenced by a relative timestamp such as `tänään 12:34` or `ma 12:34`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RelDay {
+    Today,
+    Yesterday,
+    /// The most recent given weekday, today included.
+    Weekday(Weekday),
+}
+
+/// Vocabulary used in listing timestamps. Implement this to support sites or page
+/// localizations other than the built-in [Finnish] and [Swedish], and pass it to
+/// [DateParser::with_locale](crate::DateParser::with_locale). Locales are shared by parsers
+/// that may be sent across threads, hence the `Send + Sync` bound.
+pub trait Locale: Send + Sync {
+    /// Month from its abbreviation as shown in absolute timestamps (e.g. `huh` in `21 huh 19:52`).
+    fn month_from_short(&self, s: &str) -> Option<Month>;
+    /// Day referenced by the word in relative timestamps (e.g. `tänään` in `tänään 12:34`).
+    fn relative_day(&self, s: &str) -> Option<RelDay>;
+}
+
+/// Timestamps of the Finnish tori.fi pages. This is the default locale. The Swedish relative
+/// day words `idag` and `igår` are recognized as well; see [Swedish] for the rest of the
+/// Swedish vocabulary.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Finnish;
+
+/// Timestamps of the Swedish-localized tori.fi pages.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Swedish;
+
+/// Month abbreviations of the [Finnish] locale, in calendar order.
+pub const FINNISH_MONTH_ABBREVS: [(&str, Month); 12] = [
+    ("tam", Month::January),
+    ("hel", Month::February),
+    ("maa", Month::March),
+    ("huh", Month::April),
+    ("tou", Month::May),
+    ("kes", Month::June),
+    ("hei", Month::July),
+    ("elo", Month::August),
+    ("syy", Month::September),
+    ("lok", Month::October),
+    ("mar", Month::November),
+    ("jou", Month::December),
+];
+
+/// Month abbreviations of the [Swedish] locale, in calendar order.
+pub const SWEDISH_MONTH_ABBREVS: [(&str, Month); 12] = [
+    ("jan", Month::January),
+    ("feb", Month::February),
+    ("mar", Month::March),
+    ("apr", Month::April),
+    ("maj", Month::May),
+    ("jun", Month::June),
+    ("jul", Month::July),
+    ("aug", Month::August),
+    ("sep", Month::September),
+    ("okt", Month::October),
+    ("nov", Month::November),
+    ("dec", Month::December),
+];
+
+/// Case-insensitive lookup of a month abbreviation in `table`.
+fn month_from_table(table: &[(&str, Month)], s: &str) -> Option<Month> {
+    let s = s.to_lowercase();
+    table
+        .iter()
+        .find(|(abbrev, _)| *abbrev == s)
+        .map(|(_, month)| *month)
+}
+
+impl Locale for Finnish {
+    fn month_from_short(&self, s: &str) -> Option<Month> {
+        month_from_table(&FINNISH_MONTH_ABBREVS, s)
+    }
+
+    fn relative_day(&self, s: &str) -> Option<RelDay> {
+        match s {
+            "tänään" | "idag" => Some(RelDay::Today),
+            "eilen" | "igår" => Some(RelDay::Yesterday),
+            "ma" => Some(RelDay::Weekday(Weekday::Mon)),
+            "ti" => Some(RelDay::Weekday(Weekday::Tue)),
+            "ke" => Some(RelDay::Weekday(Weekday::Wed)),
+            "to" => Some(RelDay::Weekday(Weekday::Thu)),
+            "pe" => Some(RelDay::Weekday(Weekday::Fri)),
+            "la" => Some(RelDay::Weekday(Weekday::Sat)),
+            "su" => Some(RelDay::Weekday(Weekday::Sun)),
+            _ => None,
+        }
+    }
+}
+
+impl Locale for Swedish {
+    fn month_from_short(&self, s: &str) -> Option<Month> {
+        month_from_table(&SWEDISH_MONTH_ABBREVS, s)
+    }
+
+    fn relative_day(&self, s: &str) -> Option<RelDay> {
+        match s {
+            "idag" => Some(RelDay::Today),
+            "igår" => Some(RelDay::Yesterday),
+            "mån" => Some(RelDay::Weekday(Weekday::Mon)),
+            "tis" => Some(RelDay::Weekday(Weekday::Tue)),
+            "ons" => Some(RelDay::Weekday(Weekday::Wed)),
+            "tor" => Some(RelDay::Weekday(Weekday::Thu)),
+            "fre" => Some(RelDay::Weekday(Weekday::Fri)),
+            "lör" => Some(RelDay::Weekday(Weekday::Sat)),
+            "sön" => Some(RelDay::Weekday(Weekday::Sun)),
+            _ => None,
+        }
+    }
+}
+
+/// Wraps another [Locale], adding words for today and yesterday in relative timestamps. The
+/// base locale's own words stay recognized; everything but the two added words is delegated
+/// to the base locale. See [Parser::with_relative_day_words](crate::Parser::with_relative_day_words).
+pub struct RelativeDayWords {
+    today: String,
+    yesterday: String,
+    base: Box<dyn Locale>,
+}
+
+impl RelativeDayWords {
+    pub fn new(today: &str, yesterday: &str, base: Box<dyn Locale>) -> Self {
+        RelativeDayWords {
+            today: today.to_string(),
+            yesterday: yesterday.to_string(),
+            base,
+        }
+    }
+}
+
+impl Locale for RelativeDayWords {
+    fn month_from_short(&self, s: &str) -> Option<Month> {
+        self.base.month_from_short(s)
+    }
+
+    fn relative_day(&self, s: &str) -> Option<RelDay> {
+        if s == self.today {
+            Some(RelDay::Today)
+        } else if s == self.yesterday {
+            Some(RelDay::Yesterday)
+        } else {
+            self.base.relative_day(s)
+        }
+    }
+}
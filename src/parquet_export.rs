@@ -0,0 +1,148 @@
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, BooleanArray, Int64Array, StringArray, TimestampMillisecondArray};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::errors::Result;
+
+use crate::parsing::Item;
+
+fn schema() -> Schema {
+    Schema::new(vec![
+        Field::new("item_id", DataType::Utf8, false),
+        Field::new("direction", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("description", DataType::Utf8, true),
+        Field::new("price_value", DataType::Int64, true),
+        Field::new("price_unit", DataType::Utf8, true),
+        Field::new("location", DataType::Utf8, false),
+        Field::new("seller", DataType::Utf8, true),
+        Field::new("is_company_ad", DataType::Boolean, false),
+        Field::new("href", DataType::Utf8, false),
+        Field::new("thumbnail_url", DataType::Utf8, true),
+        Field::new(
+            "posted_at",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            false,
+        ),
+    ])
+}
+
+/// Writes the items into a Parquet file at `path`, replacing the file if it exists. The
+/// columns are the same as in the `sqlite` export, but typed: the price value is an `int64`,
+/// `posted_at` a UTC timestamp in milliseconds, and missing values are nulls.
+pub fn export_to_parquet(items: &[Item], path: &Path) -> Result<()> {
+    let strings = |f: fn(&Item) -> &str| -> ArrayRef {
+        Arc::new(StringArray::from_iter_values(items.iter().map(f)))
+    };
+    let optional = |f: fn(&Item) -> Option<&str>| -> ArrayRef {
+        Arc::new(items.iter().map(f).collect::<StringArray>())
+    };
+
+    let columns: Vec<ArrayRef> = vec![
+        strings(|i| i.item_id.as_str()),
+        strings(|i| i.direction.as_str()),
+        strings(|i| i.title.as_str()),
+        optional(|i| i.description.as_deref()),
+        Arc::new(
+            items
+                .iter()
+                .map(|i| i.price.as_ref().map(|p| p.value as i64))
+                .collect::<Int64Array>(),
+        ),
+        optional(|i| i.price.as_ref().map(|p| p.unit.as_str())),
+        strings(|i| i.location.as_str()),
+        optional(|i| i.seller.as_deref()),
+        Arc::new(
+            items
+                .iter()
+                .map(|i| Some(i.is_company_ad))
+                .collect::<BooleanArray>(),
+        ),
+        strings(|i| i.href.as_str()),
+        optional(|i| i.thumbnail_url.as_deref()),
+        Arc::new(
+            TimestampMillisecondArray::from_iter_values(
+                items.iter().map(|i| i.posted_at.timestamp_millis()),
+            )
+            .with_timezone("UTC"),
+        ),
+    ];
+
+    let batch = RecordBatch::try_new(Arc::new(schema()), columns)?;
+    let mut writer = ArrowWriter::try_new(File::create(path)?, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+    use crate::utils;
+    use arrow::array::Array;
+    use chrono::TimeZone;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_export_to_parquet() {
+        let parent = Path::new(file!()).parent().unwrap();
+        let buf = utils::decode_to_string(
+            &parent.join("testdata/2023-03-25-105201-dump.html"),
+            utils::encoding_lookup("ISO_8859_15").unwrap(),
+        );
+        let helsinki = chrono_tz::Europe::Helsinki;
+        let parser = Parser::new(helsinki.with_ymd_and_hms(2023, 3, 25, 10, 52, 1).unwrap());
+        let items = parser.parse_from_string(&buf).unwrap();
+
+        let path = env::temp_dir().join(format!("tori-export-{}.parquet", std::process::id()));
+        export_to_parquet(&items, &path).unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 40);
+        assert_eq!(batch.schema().fields(), schema().fields());
+
+        let titles = batch
+            .column_by_name("title")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(titles.value(0), items[0].title);
+
+        let prices = batch
+            .column_by_name("price_value")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        for (i, item) in items.iter().enumerate() {
+            match &item.price {
+                Some(price) => assert_eq!(prices.value(i), price.value as i64),
+                None => assert!(prices.is_null(i)),
+            }
+        }
+
+        let posted_at = batch
+            .column_by_name("posted_at")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .unwrap();
+        assert_eq!(posted_at.value(0), items[0].posted_at.timestamp_millis());
+    }
+}
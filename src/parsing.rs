@@ -1,28 +1,296 @@
-use std::ops::Sub;
+use std::cmp::Ordering;
 
-use chrono::NaiveDateTime;
-use chrono::{DateTime, Datelike, Days, LocalResult, Month, NaiveTime, TimeZone, Timelike, Utc};
+use chrono::{DateTime, Duration, Utc};
 use chrono_tz::Tz;
+use encoding_rs::Encoding;
 use lazy_static::lazy_static;
 use regex::Regex;
+use scraper::ElementRef;
 use scraper::Html;
 use scraper::Selector;
+use serde_json::{json, Map, Value};
+use url::Url;
 
+use crate::dateparser::{is_relative, DateParseError, DateParseResult, DateParser};
+use crate::locale::Locale;
 use crate::utils::reformat_ws;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Item {
     pub item_id: String,
     pub direction: String,
     pub title: String,
+    /// Title as it appears in the document. Equals `title` unless
+    /// [clean_titles](Parser::clean_titles) is enabled.
+    pub title_raw: String,
+    pub description: Option<String>,
     pub price: Option<Price>,
+    /// Whether the row had a price element at all. A present but blank element yields
+    /// `price: None` with `price_present: true`.
+    pub price_present: bool,
+    /// What the price element contained, `None` if it is missing or blank. Text that is not
+    /// a price does not fail the row; it is kept here instead, with `price: None`.
+    pub price_kind: Option<PriceKind>,
     pub location: String,
+    /// Most specific category of the listing, e.g. `"Mopot"`. The `cat_geo` paragraphs do
+    /// not carry it; it is parsed from the thumbnail's alt text, which has the form
+    /// `"<title>, <category>[, <parent category>], <municipality>, Tori.fi"`. `None` for rows
+    /// without a thumbnail.
+    pub category: Option<String>,
     pub seller: Option<String>,
+    /// Link to the seller's profile or store page, if the seller paragraph contains one.
+    /// Relative links are resolved against [Parser::with_base_url], if set.
+    pub seller_href: Option<String>,
     pub is_company_ad: bool,
+    /// Whether the listing is promoted ("nostettu" or paalupaikka), and thus shown out of
+    /// chronological order.
+    pub is_promoted: bool,
     pub href: String,
     pub thumbnail_url: Option<String>,
+    pub thumbnail_srcset: Vec<(String, u32)>,
+    /// All distinct image urls in the row, in document order. The first one equals
+    /// `thumbnail_url`.
+    pub all_thumbnails: Vec<String>,
     pub posted_at_orig: String,
     pub posted_at: DateTime<Utc>,
+    /// `posted_at` in the timezone of the fetch time, for display.
+    pub posted_at_local: DateTime<Tz>,
+    /// When the listing was last bumped, on layouts that show it next to the original
+    /// posting time. Resolved like `posted_at`; `None` also if the bump time cannot be parsed.
+    pub bumped_at: Option<DateTime<Utc>>,
+    /// Outer HTML of the row the item was parsed from, if enabled with
+    /// [Parser::keep_raw_html].
+    pub raw_html: Option<String>,
+    /// Coordinates of `location` as (latitude, longitude), if a geocoder was given with
+    /// [Parser::with_geocoder] and it recognized the location.
+    pub coordinates: Option<(f64, f64)>,
+}
+
+/// Listing direction, i.e. the kind of the listing. Parsed from the Finnish phrases shown
+/// in the search results; see [Item::direction_kind].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Direction {
+    /// "Myydään"
+    Selling,
+    /// "Ostetaan"
+    Buying,
+    /// "Vuokrataan"
+    Renting,
+    /// "Halutaan vuokrata"
+    WantToRent,
+    /// "Annetaan", i.e. given away for free. These usually have a blank price element
+    /// (`price: None`) or an explicit `0 €`.
+    Giving,
+    /// Any other phrase, as is.
+    Other(String),
+}
+
+impl Direction {
+    pub fn from_phrase(phrase: &str) -> Direction {
+        match phrase {
+            "Myydään" => Direction::Selling,
+            "Ostetaan" => Direction::Buying,
+            "Vuokrataan" => Direction::Renting,
+            "Halutaan vuokrata" => Direction::WantToRent,
+            "Annetaan" => Direction::Giving,
+            _ => Direction::Other(phrase.to_string()),
+        }
+    }
+}
+
+impl Item {
+    /// Time elapsed since the item was posted, i.e. `now - posted_at`. Negative if
+    /// `posted_at` is after `now`.
+    pub fn age(&self, now: DateTime<Utc>) -> Duration {
+        now - self.posted_at
+    }
+
+    /// Whether the item was posted at most `within` before `now`.
+    pub fn is_fresh(&self, now: DateTime<Utc>, within: Duration) -> bool {
+        self.age(now) <= within
+    }
+
+    /// Structured form of the raw `direction` string.
+    pub fn direction_kind(&self) -> Direction {
+        Direction::from_phrase(&self.direction)
+    }
+
+    /// Whether the original posted-at string was relative to the fetch time (e.g.
+    /// `tänään 12:34`), in which case `posted_at` is only as accurate as the fetch time
+    /// given to the [Parser].
+    pub fn posted_at_was_relative(&self) -> bool {
+        is_relative(&self.posted_at_orig)
+    }
+
+    /// Heuristic: a euro amount written in the title, as in `"Sohva 50€"`. Intended as a
+    /// fallback for listings without a price element (`price: None`); the title may well
+    /// mention numbers that are not the asking price.
+    pub fn price_from_title(&self) -> Option<Price> {
+        let patts = TITLE_PRICE_PATT.captures(&self.title)?;
+        Some(Price {
+            value: patts[1]
+                .split_whitespace()
+                .collect::<String>()
+                .parse()
+                .ok()?,
+            unit: patts[2].to_string(),
+            per: None,
+            max: None,
+        })
+    }
+
+    /// Checks the item for values that parsed fine but are likely wrong, so that they can be
+    /// quarantined. `fetch_time` is the time the page was fetched.
+    pub fn validate(&self, fetch_time: DateTime<Utc>) -> Vec<ItemWarning> {
+        let mut warnings = vec![];
+        if self.title.trim().is_empty() {
+            warnings.push(ItemWarning::EmptyTitle);
+        }
+        if let Some(price) = &self.price {
+            if price.value == 0 && price.max.is_none() {
+                warnings.push(ItemWarning::ZeroPrice);
+            }
+        }
+        if self.posted_at - fetch_time > Duration::minutes(FUTURE_TOLERANCE_MINUTES) {
+            warnings.push(ItemWarning::PostedInFuture);
+        }
+        warnings
+    }
+
+    /// `href` without tracking query parameters (`utm_*`, and tori's own `ca` and `w`), for
+    /// deduplicating listings by url. The path, which contains the listing id, and any other
+    /// parameters are kept. Returns `href` as is if it is not a valid url.
+    pub fn canonical_href(&self) -> String {
+        let mut url = match Url::parse(&self.href) {
+            Ok(url) => url,
+            Err(_) => return self.href.clone(),
+        };
+        let kept: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(key, _)| !is_tracking_param(key))
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+        if kept.is_empty() {
+            url.set_query(None);
+        } else {
+            url.query_pairs_mut().clear().extend_pairs(kept);
+        }
+        url.to_string()
+    }
+
+    /// Hash of the listing's content: id, title, price, location, direction and seller. The
+    /// posting time is left out, as relative timestamps resolve differently between scrapes.
+    /// Comparing fingerprints of the same id across scrapes reveals edited listings. The
+    /// hash function (64-bit FNV-1a) is fixed, so fingerprints can be stored.
+    pub fn content_fingerprint(&self) -> u64 {
+        let price = self.price.as_ref();
+        let value = price.map(|p| p.value.to_string());
+        let max = price.and_then(|p| p.max).map(|m| m.to_string());
+        let fields = [
+            Some(self.item_id.as_str()),
+            Some(self.title.as_str()),
+            value.as_deref(),
+            price.map(|p| p.unit.as_str()),
+            price.and_then(|p| p.per.as_deref()),
+            max.as_deref(),
+            Some(self.location.as_str()),
+            Some(self.direction.as_str()),
+            self.seller.as_deref(),
+        ];
+
+        let mut hash = FNV_OFFSET_BASIS;
+        for field in fields {
+            // a leading marker byte tells a missing field apart from an empty one, and the
+            // trailing separator keeps the field boundaries from shifting.
+            let bytes = field.map(|f| f.as_bytes());
+            for byte in [bytes.is_some() as u8]
+                .iter()
+                .chain(bytes.unwrap_or_default())
+                .chain(&[0xff])
+            {
+                hash ^= *byte as u64;
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        }
+        hash
+    }
+
+    /// GeoJSON `Feature` with a `Point` geometry at [coordinates](Item::coordinates) and
+    /// the [to_json_map](Item::to_json_map) fields as properties, for plotting listings on
+    /// a map. `None` if the item has no coordinates.
+    pub fn to_geojson_feature(&self) -> Option<Value> {
+        let (lat, lon) = self.coordinates?;
+        Some(json!({
+            "type": "Feature",
+            // GeoJSON orders positions as longitude, latitude
+            "geometry": {"type": "Point", "coordinates": [lon, lat]},
+            "properties": self.to_json_map(),
+        }))
+    }
+
+    /// Flat JSON object of the item's fields, for pipelines that map keys to columns. The
+    /// price is split into `price_value`, `price_unit`, `price_per` and `price_max`, and
+    /// `posted_at` is formatted as RFC 3339. Missing values are `null`.
+    pub fn to_json_map(&self) -> Map<String, Value> {
+        let mut map = Map::new();
+        map.insert("item_id".into(), json!(self.item_id));
+        map.insert("direction".into(), json!(self.direction));
+        map.insert("title".into(), json!(self.title));
+        map.insert("description".into(), json!(self.description));
+        map.insert(
+            "price_value".into(),
+            json!(self.price.as_ref().map(|p| p.value)),
+        );
+        map.insert(
+            "price_unit".into(),
+            json!(self.price.as_ref().map(|p| &p.unit)),
+        );
+        map.insert(
+            "price_per".into(),
+            json!(self.price.as_ref().and_then(|p| p.per.as_ref())),
+        );
+        map.insert(
+            "price_max".into(),
+            json!(self.price.as_ref().and_then(|p| p.max)),
+        );
+        map.insert("price_present".into(), json!(self.price_present));
+        map.insert(
+            "price_kind".into(),
+            json!(self.price_kind.as_ref().map(|k| k.as_str())),
+        );
+        map.insert(
+            "price_raw".into(),
+            json!(match &self.price_kind {
+                Some(PriceKind::Raw(text)) => Some(text),
+                _ => None,
+            }),
+        );
+        map.insert("location".into(), json!(self.location));
+        map.insert("category".into(), json!(self.category));
+        map.insert("seller".into(), json!(self.seller));
+        map.insert("seller_href".into(), json!(self.seller_href));
+        map.insert("is_company_ad".into(), json!(self.is_company_ad));
+        map.insert("href".into(), json!(self.href));
+        map.insert("thumbnail_url".into(), json!(self.thumbnail_url));
+        map.insert("posted_at_orig".into(), json!(self.posted_at_orig));
+        map.insert("posted_at".into(), json!(self.posted_at.to_rfc3339()));
+        map.insert(
+            "bumped_at".into(),
+            json!(self.bumped_at.map(|t| t.to_rfc3339())),
+        );
+        map
+    }
+}
+
+/// Suspicious but non-fatal findings about a parsed item, see [Item::validate].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemWarning {
+    /// The title is empty or only whitespace.
+    EmptyTitle,
+    /// The price is exactly zero. Legitimate for giveaways, but often a placeholder.
+    ZeroPrice,
+    /// `posted_at` is later than the fetch time, beyond a small tolerance for clock skew.
+    PostedInFuture,
 }
 
 #[derive(Debug, PartialEq)]
@@ -37,12 +305,50 @@ pub enum ItemAttribute {
     Direction,
 }
 
+impl ItemAttribute {
+    /// All variants, in declaration order.
+    pub const ALL: [ItemAttribute; 8] = [
+        ItemAttribute::ID,
+        ItemAttribute::Title,
+        ItemAttribute::Href,
+        ItemAttribute::CompanyAd,
+        ItemAttribute::Img,
+        ItemAttribute::PostedAt,
+        ItemAttribute::Location,
+        ItemAttribute::Direction,
+    ];
+
+    /// Stable snake_case code of the attribute, e.g. `"posted_at"`. Suitable for logs and
+    /// metrics; unlike the `Debug` output, the codes will not change.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemAttribute::ID => "id",
+            ItemAttribute::Title => "title",
+            ItemAttribute::Href => "href",
+            ItemAttribute::CompanyAd => "company_ad",
+            ItemAttribute::Img => "img",
+            ItemAttribute::PostedAt => "posted_at",
+            ItemAttribute::Location => "location",
+            ItemAttribute::Direction => "direction",
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum ItemParseErrorKind {
     MissingAttribute(ItemAttribute),
     UnexpectedValue(ItemAttribute, String),
     InvalidPrice(String),
+    /// The bounds of a price range have different units, e.g. `"100 € - 200 kr"`. Holds the
+    /// whole input.
+    PriceUnitMismatch(String),
     InvalidDate(DateParseError),
+    /// The document looks like a results page but contains no item rows; see
+    /// [Parser::parse_strict].
+    NoRowsFound,
+    /// The input clearly is not HTML, e.g. a JSON error body; see
+    /// [Parser::parse_from_string].
+    NotHtml,
 }
 
 #[derive(Debug, PartialEq)]
@@ -50,25 +356,51 @@ pub struct ItemParseError {
     pub item_idx: usize,
     pub item_id: Option<String>,
     pub error: ItemParseErrorKind,
+    /// Beginning of the offending row's outer HTML, to help locate it in the document.
+    pub context: Option<String>,
 }
 
-#[derive(Debug, PartialEq)]
-pub enum DateParseError {
-    InvalidHighlevelStructure(String),
-    InvalidDay(String),
-    InvalidTime(String),
-    InvalidMonth(String),
-    InvalidRelativeDay(String),
-    ArithmeticProblem,
+/// Row counters collected by [Parser::parse_document_with_stats].
+#[derive(Debug, Default, PartialEq)]
+pub struct ParseStats {
+    /// Number of elements matching the row selector.
+    pub rows_seen: usize,
+    /// Number of rows successfully parsed into an [Item].
+    pub items_ok: usize,
 }
 
-pub type DateParseResult<T> = Result<T, DateParseError>;
+impl ParseStats {
+    /// Number of rows that were seen but could not be parsed.
+    pub fn rows_skipped(&self) -> usize {
+        self.rows_seen - self.items_ok
+    }
+}
+
+/// Number of elements each configured selector matched across a whole document. See
+/// [Parser::debug_counts].
+#[derive(Debug, Default, PartialEq)]
+pub struct SelectorCounts {
+    pub rows: usize,
+    pub titles: usize,
+    pub descriptions: usize,
+    pub prices: usize,
+    pub images: usize,
+    pub posted_at: usize,
+    pub combined: usize,
+}
 
 type ItemParseResult<T> = Result<T, ItemParseError>;
 
 /// Implements parsing of tori.fi search results page. You can parse either
 /// the whole content (HTML document) or some parts of if (e.g. timestamps).
 ///
+/// The parser keeps two views of the fetch time: the [server_time](Parser::server_time),
+/// an instant in UTC, and the [timezone](Parser::timezone) in which the page displays its
+/// timestamps. Relative days (`tänään`, `eilen`) and missing years are resolved on the
+/// calendar of that timezone, while the check whether a timestamp is in the future compares
+/// UTC instants against the server time. Use [from_utc](Parser::from_utc) when the machine
+/// running the scraper is in a different timezone than the site's users.
+///
 /// # Examples
 /// ```compile_fail
 /// use tori_scrape::{Parser, Item};
@@ -80,79 +412,415 @@ type ItemParseResult<T> = Result<T, ItemParseError>;
 ///
 /// let results: ItemParseResult<Vec<Item>> = parser.parse_from_string(&buf);
 /// ```
+///
+/// [parse_from_string](Parser::parse_from_string) parses the HTML on every call. When
+/// running several queries against the same page, parse the DOM once and pass it to the
+/// `&Html` based methods instead:
+///
+/// ```
+/// use chrono::TimeZone;
+/// use scraper::Html;
+/// use tori_scrape::Parser;
+///
+/// let fetch_time = chrono_tz::Europe::Helsinki
+///     .with_ymd_and_hms(2023, 3, 25, 10, 52, 1)
+///     .unwrap();
+/// let parser = Parser::new(fetch_time);
+///
+/// let doc = Html::parse_document("<html><body></body></html>");
+/// let items = parser.parse_document(&doc).unwrap();
+/// let ids = parser.parse_ids(&doc);
+/// assert_eq!(items.len(), ids.len());
+/// ```
 pub struct Parser {
-    user_today: DateTime<chrono_tz::Tz>,
-    user_yesterday: DateTime<chrono_tz::Tz>,
+    dates: DateParser,
+    selectors: Selectors,
+    clean_titles: bool,
+    keep_raw_html: bool,
+    treat_zero_as_none: bool,
+    number_format: NumberFormat,
+    base_url: Option<Url>,
+    company_ad_true: Vec<String>,
+    company_ad_false: Vec<String>,
+    geocoder: Option<Box<dyn Fn(&str) -> Option<(f64, f64)> + Send + Sync>>,
+}
+
+/// CSS selectors used for locating item rows and their fields in a search results page.
+/// The [Default] implementation matches the tori.fi desktop layout; override individual
+/// fields if the markup differs, and pass the result to [Parser::with_selectors].
+/// All selectors except `results`, `row`, `active_region` and `empty_results` are matched
+/// within a single row element.
+#[derive(Debug, Clone)]
+pub struct Selectors {
+    /// Matches the container of the search results. Present even if the search had no hits.
+    pub results: Selector,
+    /// Matches each item row in the document.
+    pub row: Selector,
+    pub title: Selector,
+    /// Unverified: the bundled dumps have no description element in their rows, so the
+    /// default `.li-description` is a guess and [Item::description] is `None` for them.
+    pub description: Selector,
+    pub price: Selector,
+    pub image: Selector,
+    pub posted_at: Selector,
+    /// Paragraphs containing location, direction and seller, in that order.
+    pub combined: Selector,
+    /// Marker present only in promoted rows.
+    pub promoted: Selector,
+    /// Secondary timestamp telling when the listing was bumped, if shown.
+    /// Unverified: the bundled dumps show no bump time, so the default `.bumped_date` is a
+    /// guess.
+    pub bumped_at: Selector,
+    /// The region selected in the search form, e.g. "Koko Suomi".
+    pub active_region: Selector,
+    /// The "Haku ei tuottanut tuloksia" (no results) message shown instead of rows.
+    /// Unverified: the default `.no_results` is a guess, as none of the bundled dumps is a
+    /// no-results page. Override it if it does not match the live markup.
+    pub empty_results: Selector,
+}
+
+impl Default for Selectors {
+    fn default() -> Self {
+        Selectors {
+            results: Selector::parse("div.list_mode_thumb").unwrap(),
+            row: Selector::parse("a[data-row]").unwrap(),
+            title: Selector::parse("div .li-title").unwrap(),
+            description: Selector::parse("div .li-description").unwrap(),
+            price: Selector::parse("p .list_price, .ineuros").unwrap(),
+            image: Selector::parse("div .item_image[src]").unwrap(),
+            posted_at: Selector::parse("div .date_image").unwrap(),
+            combined: Selector::parse("div .cat_geo > p").unwrap(),
+            promoted: Selector::parse(".polepos_marker, .bumped_date_cat").unwrap(),
+            bumped_at: Selector::parse(".bumped_date").unwrap(),
+            active_region: Selector::parse("select#searcharea_expanded option[selected]").unwrap(),
+            empty_results: Selector::parse(".no_results").unwrap(),
+        }
+    }
+}
+
+/// Kind of the contents of a row's price element, see [Item::price_kind].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceKind {
+    /// A number, available in [Item::price].
+    Fixed,
+    /// The price is to be agreed on, e.g. `"Sop."` (sovitaan) or `"Tarjoa"`.
+    Negotiable,
+    /// Any other text, with whitespace normalized.
+    Raw(String),
+}
+
+impl PriceKind {
+    /// Classifies price text that [price_parse] rejected.
+    pub fn from_text(text: &str) -> PriceKind {
+        let text = reformat_ws(text);
+        match text.to_lowercase().trim_end_matches('.') {
+            "sop" | "sovitaan" | "sopimuksen mukaan" | "tarjoa" => PriceKind::Negotiable,
+            _ => PriceKind::Raw(text),
+        }
+    }
+
+    /// Stable snake_case code of the kind, e.g. `"negotiable"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceKind::Fixed => "fixed",
+            PriceKind::Negotiable => "negotiable",
+            PriceKind::Raw(_) => "raw",
+        }
+    }
 }
 
 /// Represents item price, parsed from a posting. The unit is usually "€".
-#[derive(Debug, PartialEq)]
+///
+/// Prices are ordered by `value`. Ties are broken by `max`, `unit` and `per` only to keep
+/// the order consistent with equality; comparing prices in different currencies is not
+/// meaningful.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Price {
+    /// The price, or the lower bound if the price is a range.
     pub value: i32,
     pub unit: String,
+    /// Optional per-unit suffix, e.g. `"kk"` (per month) in `"500 €/kk"`.
+    pub per: Option<String>,
+    /// Upper bound of a price range such as `"100–200 €"`.
+    pub max: Option<i32>,
+}
+
+impl Price {
+    /// ISO 4217 code of the price unit, or `None` if the unit glyph is not recognized.
+    pub fn currency_code(&self) -> Option<&'static str> {
+        match self.unit.as_str() {
+            "€" => Some("EUR"),
+            "$" => Some("USD"),
+            "£" => Some("GBP"),
+            "EUR" => Some("EUR"),
+            "USD" => Some("USD"),
+            "GBP" => Some("GBP"),
+            "SEK" => Some("SEK"),
+            "NOK" => Some("NOK"),
+            "DKK" => Some("DKK"),
+            // "kr" is shared by several currencies
+            _ => None,
+        }
+    }
+}
+
+impl Ord for Price {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value
+            .cmp(&other.value)
+            .then_with(|| self.max.cmp(&other.max))
+            .then_with(|| self.unit.cmp(&other.unit))
+            .then_with(|| self.per.cmp(&other.per))
+    }
+}
+
+impl PartialOrd for Price {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Class of the `cat_geo` paragraph holding the store name of a company ad.
+const SELLER_CLASS: &str = "list_store_name";
+
+/// Query parameters stripped by [Item::canonical_href], besides `utm_*`.
+const TRACKING_PARAMS: [&str; 4] = ["ca", "w", "fbclid", "gclid"];
+
+/// How far past the fetch time `posted_at` may be before [Item::validate] flags it.
+const FUTURE_TOLERANCE_MINUTES: i64 = 5;
+
+/// Maximum length (in characters) of the row snippet attached to [ItemParseError].
+const ERROR_CONTEXT_LEN: usize = 200;
+
+/// Parameters of the 64-bit FNV-1a hash used by [Item::content_fingerprint].
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Currency symbols and tokens accepted after a price value: a symbol, `kr` or one of the
+/// ISO 4217 codes known to [Price::currency_code]. The codes are listed explicitly, as any
+/// three capital letters would also match words such as "ALV" (VAT).
+const PRICE_UNIT: &str = r"(€|\$|£|kr\b|(?:EUR|USD|GBP|SEK|NOK|DKK)\b)";
+
+lazy_static! {
+    static ref PRICE_PATT: Regex = Regex::new(&format!(
+        r"\s*([0-9][0-9\s,]*)\s+{}(?:\s*/\s*(\w+))?\s*",
+        PRICE_UNIT
+    ))
+    .unwrap();
+    static ref PRICE_RANGE_PATT: Regex = Regex::new(&format!(
+        r"\s*([0-9][0-9\s,]*?)\s*(?:{0}\s*)?[-–—]\s*([0-9][0-9\s,]*)\s+{0}(?:\s*/\s*(\w+))?\s*",
+        PRICE_UNIT
+    ))
+    .unwrap();
+    // some locale variants put the unit first, e.g. "€ 50" or "€50". only symbols are
+    // accepted here.
+    static ref PRICE_UNIT_FIRST_PATT: Regex =
+        Regex::new(r"\s*(€|\$|£)\s*([0-9][0-9\s,]*)(?:\s*/\s*(\w+))?\s*").unwrap();
+    // only the euro sign is accepted; ISO codes would match model names such as "2005 TDI".
+    static ref TITLE_PRICE_PATT: Regex = Regex::new(r"\b([0-9][0-9 ]*?)\s*(€)").unwrap();
+    static ref IMG_SELECTOR: Selector = Selector::parse("img").unwrap();
+    static ref LINK_SELECTOR: Selector = Selector::parse("a[href]").unwrap();
+}
+
+/// Price with a decimal value, see [Price::parse_decimal].
+#[cfg(feature = "rust_decimal")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecimalPrice {
+    pub value: rust_decimal::Decimal,
+    pub unit: String,
+    pub per: Option<String>,
 }
 
+#[cfg(feature = "rust_decimal")]
 lazy_static! {
-    static ref PRICE_PATT: Regex = Regex::new(r"\s*([0-9][0-9\s]*)\s+(€)\s*").unwrap();
-    static ref ROW_SELECTOR: Selector = Selector::parse("a[data-row]").unwrap();
-    static ref TITLE_SELECTOR: Selector = Selector::parse("div .li-title").unwrap();
-    static ref PRICE_SELECTOR: Selector = Selector::parse("p .list_price, .ineuros").unwrap();
-    static ref IMAGE_SELECTOR: Selector = Selector::parse("div .item_image[src]").unwrap();
-    static ref POSTED_AT_SELECTOR: Selector = Selector::parse("div .date_image").unwrap();
-    static ref COMBINED_SELECTOR: Selector = Selector::parse("div .cat_geo > p").unwrap();
-    static ref REL_TIME: Regex = Regex::new(r"\s*(eilen|tänään)\s+(\d{2}:\d{2})\s*").unwrap();
-    static ref ABS_TIME: Regex =
-        Regex::new(r"\s*(\d{1,2})\s+([a-zA-Z]{3})\s+(\d{2}:\d{2})\s*").unwrap();
-}
-
-/// Parse price string such as "1 234 €" into structured form.
+    static ref DECIMAL_PRICE_PATT: Regex = Regex::new(&format!(
+        r"\s*([0-9][0-9\s]*(?:,[0-9]+)?)\s*{}(?:\s*/\s*(\w+))?\s*",
+        PRICE_UNIT
+    ))
+    .unwrap();
+}
+
+#[cfg(feature = "rust_decimal")]
+impl Price {
+    /// Like [price_parse], but keeps the cents of prices such as "19 900,50 €" by parsing
+    /// the value as a [Decimal](rust_decimal::Decimal). Ranges are not supported.
+    pub fn parse_decimal(input: &str) -> Result<DecimalPrice, ItemParseErrorKind> {
+        use std::str::FromStr;
+
+        let invalid = || ItemParseErrorKind::InvalidPrice(input.to_string());
+        let patts = DECIMAL_PRICE_PATT.captures(input).ok_or_else(invalid)?;
+        let value_s = patts[1]
+            .split_whitespace()
+            .collect::<String>()
+            .replace(',', ".");
+        Ok(DecimalPrice {
+            value: rust_decimal::Decimal::from_str(&value_s).map_err(|_| invalid())?,
+            unit: patts[2].to_string(),
+            per: patts.get(3).map(|m| m.as_str().to_string()),
+        })
+    }
+}
+
+/// How separators in price values are interpreted, see [Parser::number_format].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum NumberFormat {
+    /// Space separates thousands and comma is the decimal separator, as on tori.fi. Prices
+    /// are whole euros, so values with a decimal part (e.g. `"1,599 €"`, i.e. 1.599 €) are
+    /// rejected; see `Price::parse_decimal` for keeping cents.
+    #[default]
+    Finnish,
+    /// Comma separates thousands, so `"1,599 €"` is 1599 €.
+    Generic,
+}
+
+/// Parse price string such as "1 234 €" or "500 €/kk" into structured form. Ranges such as
+/// "100–200 €" (hyphen, en dash or em dash) are parsed into `value` and `max`; the unit may
+/// be repeated after the lower bound, as in "100 € - 200 €", but must then match. The unit may
+/// also precede the value, as in "€ 50". Separators are interpreted as in
+/// [NumberFormat::Finnish]; see [price_parse_with] for other formats.
 pub fn price_parse(input: &str) -> Result<Price, ItemParseErrorKind> {
+    price_parse_with(input, NumberFormat::Finnish)
+}
+
+/// Like [price_parse], for callers that only care whether there is a usable price: empty,
+/// negotiable (`"Sop."`) and otherwise unparseable input all yield `None`. Use
+/// [price_parse] when the reason for a missing price matters.
+pub fn price_parse_opt(input: &str) -> Option<Price> {
+    if input.trim().is_empty() {
+        return None;
+    }
+    price_parse(input).ok()
+}
+
+/// Like [price_parse], with the given interpretation of separators in the value.
+pub fn price_parse_with(input: &str, format: NumberFormat) -> Result<Price, ItemParseErrorKind> {
     // note: input must not be empty
-    match PRICE_PATT.captures(input) {
-        Some(patts) => {
-            let (_, [value_s, unit]) = patts.extract();
-            let value = value_s
-                .split_whitespace()
-                .collect::<String>()
-                .parse::<i32>()
-                .map_err(|_| ItemParseErrorKind::InvalidPrice(input.to_string()))?;
-            Ok(Price {
-                value: value,
-                unit: unit.to_string(),
-            })
+    let parse_value = |value_s: &str| {
+        let digits = match format {
+            NumberFormat::Finnish if value_s.contains(',') => None,
+            _ => Some(
+                value_s
+                    .split(|c: char| c.is_whitespace() || c == ',')
+                    .collect::<String>(),
+            ),
+        };
+        digits
+            .and_then(|digits| digits.parse::<i32>().ok())
+            .ok_or_else(|| ItemParseErrorKind::InvalidPrice(input.to_string()))
+    };
+
+    // the single price pattern would match the upper bound of a range alone, so
+    // ranges must be checked first.
+    // note: the per-unit group is optional, so Captures::extract() is not usable here.
+    if let Some(patts) = PRICE_RANGE_PATT.captures(input) {
+        let value = parse_value(&patts[1])?;
+        let max = parse_value(&patts[3])?;
+        if value > max {
+            return Err(ItemParseErrorKind::InvalidPrice(input.to_string()));
         }
-        None => Err(ItemParseErrorKind::InvalidPrice(input.to_string())),
+        if patts.get(2).is_some_and(|unit| unit.as_str() != &patts[4]) {
+            return Err(ItemParseErrorKind::PriceUnitMismatch(input.to_string()));
+        }
+        Ok(Price {
+            value: value,
+            unit: patts[4].to_string(),
+            per: patts.get(5).map(|m| m.as_str().to_string()),
+            max: Some(max),
+        })
+    } else if let Some(patts) = PRICE_PATT.captures(input) {
+        Ok(Price {
+            value: parse_value(&patts[1])?,
+            unit: patts[2].to_string(),
+            per: patts.get(3).map(|m| m.as_str().to_string()),
+            max: None,
+        })
+    } else if let Some(patts) = PRICE_UNIT_FIRST_PATT.captures(input) {
+        Ok(Price {
+            value: parse_value(&patts[2])?,
+            unit: patts[1].to_string(),
+            per: patts.get(3).map(|m| m.as_str().to_string()),
+            max: None,
+        })
+    } else {
+        Err(ItemParseErrorKind::InvalidPrice(input.to_string()))
+    }
+}
+
+/// Strip the last `" | ..."` or `" - ..."` segment of a title, if any.
+fn strip_title_suffix(title: &str) -> &str {
+    match [" | ", " - "]
+        .iter()
+        .filter_map(|sep| title.rfind(sep))
+        .max()
+    {
+        Some(pos) if pos > 0 => title[..pos].trim_end(),
+        _ => title,
     }
 }
 
-fn parse_month_short(month_short_name: &str) -> DateParseResult<Month> {
-    match &month_short_name.to_lowercase()[..] {
-        "tam" => Ok(Month::January),
-        "hel" => Ok(Month::February),
-        "maa" => Ok(Month::March),
-        "huh" => Ok(Month::April),
-        "tou" => Ok(Month::May),
-        "kes" => Ok(Month::June),
-        "hei" => Ok(Month::July),
-        "elo" => Ok(Month::August),
-        "syy" => Ok(Month::September),
-        "lok" => Ok(Month::October),
-        "mar" => Ok(Month::November),
-        "jou" => Ok(Month::December),
-        _ => Err(DateParseError::InvalidMonth(month_short_name.to_string())),
+/// Text of the posted-at element. Some layouts render the timestamp as an image, in which
+/// case the `alt` (or `title`) of the child `img` is used instead.
+fn posted_at_text(node: ElementRef) -> String {
+    let text = reformat_ws(&node.text().collect::<String>());
+    if !text.is_empty() {
+        return text;
     }
+    node.select(&IMG_SELECTOR)
+        .next()
+        .and_then(|img| img.value().attr("alt").or(img.value().attr("title")))
+        .map(reformat_ws)
+        .unwrap_or_default()
+}
+
+/// Cheap sanity check that `buf` is markup: the first non-whitespace character (after an
+/// optional byte order mark) must open a tag.
+fn looks_like_html(buf: &str) -> bool {
+    buf.trim_start_matches('\u{FEFF}')
+        .trim_start()
+        .starts_with('<')
 }
 
-fn parse_hh_mm(time: &str) -> DateParseResult<NaiveTime> {
-    NaiveTime::parse_from_str(time, "%H:%M")
-        .map_err(|_| DateParseError::InvalidTime(time.to_string()))
+/// Whether the query parameter only tracks the source of the click.
+fn is_tracking_param(key: &str) -> bool {
+    key.starts_with("utm_") || TRACKING_PARAMS.contains(&key)
 }
 
-fn parse_day(day: &str) -> DateParseResult<u32> {
-    match day.parse::<u32>() {
-        Ok(d) if d >= 1 && d <= 31 => Ok(d),
-        _ => Err(DateParseError::InvalidDay(day.to_string())),
+/// Url of an image element. Lazy-loaded images carry a `data:` placeholder in `src` and the
+/// real url in `data-src` or `data-lazy`; placeholders are never returned.
+fn image_url(img: ElementRef) -> Option<&str> {
+    ["src", "data-src", "data-lazy"]
+        .iter()
+        .filter_map(|attr| img.value().attr(attr))
+        .find(|url| !url.starts_with("data:"))
+}
+
+/// Extract the category from thumbnail alt text such as `"Sohva, Sohvat, Huonekalut, Espoo,
+/// Tori.fi"`. The title is stripped by prefix since it may itself contain commas.
+fn category_from_alt(alt: &str, title: &str) -> Option<String> {
+    let rest = alt
+        .strip_prefix(title.trim())?
+        .trim_start()
+        .strip_prefix(',')?;
+    // [category, (parent category,) municipality, "Tori.fi"]
+    let parts: Vec<&str> = rest.split(", ").collect();
+    if parts.len() < 3 {
+        return None;
     }
+    Some(reformat_ws(parts[0])).filter(|s| !s.is_empty())
+}
+
+/// Parse `srcset` attribute value such as `"a.jpg 320w, b.jpg 640w"` into (url, width) pairs.
+/// Candidates without a width descriptor are skipped.
+fn parse_srcset(srcset: &str) -> Vec<(String, u32)> {
+    srcset
+        .split(',')
+        .filter_map(|candidate| {
+            let mut parts = candidate.split_whitespace();
+            let url = parts.next()?;
+            let width = parts.next()?.strip_suffix('w')?.parse::<u32>().ok()?;
+            Some((url.to_string(), width))
+        })
+        .collect()
 }
 
 impl Parser {
@@ -160,237 +828,610 @@ impl Parser {
     /// to be able to decode relative/ambiguous timestamps (see [parse_posted_at](Parser::parse_posted_at)).
     /// Because of this, you should create separate parser instance for each fetched page.
     pub fn new(fetch_time: DateTime<Tz>) -> Self {
+        Self::with_selectors(fetch_time, Selectors::default())
+    }
+
+    /// Like [new](Parser::new), but takes the fetch time as UTC together with the timezone
+    /// in which the page renders its timestamps, mirroring [DateParser::new].
+    pub fn from_utc(server_time: DateTime<Utc>, tz: Tz) -> Self {
+        Self::new(server_time.with_timezone(&tz))
+    }
+
+    /// Use the given locale for interpreting month and day names in timestamps. The default
+    /// is [Finnish](crate::locale::Finnish). Words added with
+    /// [with_relative_day_words](Parser::with_relative_day_words) remain recognized,
+    /// regardless of the order of the calls.
+    pub fn with_locale(mut self, locale: Box<dyn Locale>) -> Self {
+        self.dates = self.dates.with_locale(locale);
+        self
+    }
+
+    /// Recognize `today` and `yesterday` as the words for the current and previous day in
+    /// relative timestamps such as `tänään 12:34`, in addition to those of the locale.
+    pub fn with_relative_day_words(mut self, today: &str, yesterday: &str) -> Self {
+        self.dates = self.dates.with_relative_day_words(today, yesterday);
+        self
+    }
+
+    /// Like [new](Parser::new), but with custom selectors for locating items in the document.
+    pub fn with_selectors(fetch_time: DateTime<Tz>, selectors: Selectors) -> Self {
         Parser {
-            user_today: fetch_time,
-            user_yesterday: fetch_time.sub(Days::new(1)),
+            dates: DateParser::new(fetch_time.with_timezone(&Utc), fetch_time.timezone()),
+            selectors: selectors,
+            clean_titles: false,
+            keep_raw_html: false,
+            treat_zero_as_none: false,
+            number_format: NumberFormat::default(),
+            base_url: None,
+            company_ad_true: vec!["1".to_string()],
+            company_ad_false: vec!["0".to_string()],
+            geocoder: None,
         }
     }
 
-    fn parse_rel_time(&self, relday_s: &str, hhmm_s: &str) -> DateParseResult<DateTime<Utc>> {
-        let naive_time = parse_hh_mm(hhmm_s)?;
+    /// Look up [Item::coordinates] with `geocoder`, which maps a location such as
+    /// `"Helsinki"` to (latitude, longitude). tori.fi does not provide coordinates itself.
+    /// The geocoder is called once per item, so it should cache any expensive lookups.
+    pub fn with_geocoder(
+        mut self,
+        geocoder: impl Fn(&str) -> Option<(f64, f64)> + Send + Sync + 'static,
+    ) -> Self {
+        self.geocoder = Some(Box::new(geocoder));
+        self
+    }
 
-        let naive_date = match relday_s {
-            "tänään" => Ok(self.user_today.date_naive()),
-            "eilen" => Ok(self.user_yesterday.date_naive()),
-            _ => Err(DateParseError::InvalidRelativeDay(relday_s.to_string())),
-        }?;
+    /// Values of the `data-company-ad` attribute meaning a company ad (`truthy`) and a
+    /// private one (`falsy`). The defaults are `"1"` and `"0"`. Any other value fails the
+    /// row with [UnexpectedValue](ItemParseErrorKind::UnexpectedValue).
+    pub fn company_ad_values(mut self, truthy: &[&str], falsy: &[&str]) -> Self {
+        self.company_ad_true = truthy.iter().map(|s| s.to_string()).collect();
+        self.company_ad_false = falsy.iter().map(|s| s.to_string()).collect();
+        self
+    }
 
-        let date = NaiveDateTime::new(naive_date, naive_time);
+    /// Resolve relative links found in the page against `base`, typically the url the page
+    /// was fetched from. Currently applies to [Item::seller_href]; without a base url,
+    /// links are kept as they appear in the page.
+    pub fn with_base_url(mut self, base: Url) -> Self {
+        self.base_url = Some(base);
+        self
+    }
 
-        match self.user_today.timezone().from_local_datetime(&date) {
-            LocalResult::Single(new_ts) => Ok(new_ts.with_timezone(&Utc)),
-            _ => Err(DateParseError::ArithmeticProblem),
+    fn resolve_url(&self, href: &str) -> String {
+        match &self.base_url {
+            Some(base) => base
+                .join(href)
+                .map(|url| url.to_string())
+                .unwrap_or_else(|_| href.to_string()),
+            None => href.to_string(),
         }
     }
 
-    fn parse_abs_time(
-        &self,
-        day_s: &str,
-        month_s: &str,
-        hhmm_s: &str,
-    ) -> DateParseResult<DateTime<Utc>> {
-        let day = parse_day(day_s)?;
-        let month = parse_month_short(month_s)?;
-        let naive_time = parse_hh_mm(hhmm_s)?;
-        let new_ts_maybe = self.user_today.timezone().with_ymd_and_hms(
-            self.user_today.year(),
-            month.number_from_month(),
-            day,
-            naive_time.hour(),
-            naive_time.minute(),
-            0,
-        );
-
-        let new_ts = match new_ts_maybe {
-            LocalResult::Single(new_ts) => Ok(new_ts),
-            _ => Err(DateParseError::ArithmeticProblem),
-        }?;
+    /// How to interpret separators in price values. The default is
+    /// [Finnish](NumberFormat::Finnish).
+    pub fn number_format(mut self, format: NumberFormat) -> Self {
+        self.number_format = format;
+        self
+    }
+
+    /// Strip a trailing `" | ..."` or `" - ..."` segment (typically a category) from item
+    /// titles, e.g. `"Sohva | Huonekalut"` becomes `"Sohva"`. The unmodified title is kept
+    /// in [Item::title_raw]. Disabled by default.
+    pub fn clean_titles(mut self, enabled: bool) -> Self {
+        self.clean_titles = enabled;
+        self
+    }
+
+    /// Treat a price of exactly `0 €` as if the price element were blank: `price` and
+    /// `price_kind` become `None`, while `price_present` stays true. Ranges starting from
+    /// zero are kept. Disabled by default. Genuine giveaways can still be recognized by
+    /// their direction, [Direction::Giving]; a textual price such as `"Ilmainen"` is not a
+    /// number and is kept in [Item::price_kind] regardless of this setting.
+    pub fn treat_zero_as_none(mut self, enabled: bool) -> Self {
+        self.treat_zero_as_none = enabled;
+        self
+    }
+
+    /// Keep the outer HTML of each row in [Item::raw_html], for inspecting the source of
+    /// unexpected values. Disabled by default, as it multiplies the memory used per item.
+    pub fn keep_raw_html(mut self, enabled: bool) -> Self {
+        self.keep_raw_html = enabled;
+        self
+    }
 
-        // timestamp can be in the future; check manually since we lack the actual year.
-        // this assumes no item can be listed for over a year.
-        let y_offset = if new_ts > self.user_today { 1 } else { 0 };
+    /// The fetch time given to [new](Parser::new), which relative timestamps are resolved
+    /// against.
+    pub fn fetch_time(&self) -> DateTime<Tz> {
+        self.dates.fetch_time()
+    }
 
-        let new_ts = new_ts
-            .with_year(new_ts.year() - y_offset)
-            .ok_or(DateParseError::ArithmeticProblem)?;
+    /// The timezone of the fetch time, in which timestamps are interpreted.
+    pub fn timezone(&self) -> Tz {
+        self.dates.timezone()
+    }
 
-        Ok(new_ts.with_timezone(&Utc))
+    /// The instant the page was fetched.
+    pub fn server_time(&self) -> DateTime<Utc> {
+        self.dates.server_time()
     }
 
-    /// Parse timestamp of an item listing (when it was posted). The input string can contain
-    /// either 1) _absolute_ timestamp such as `15 huh 12:45` or 2) _relative_ timestamp such
-    /// as `tänään 12:34` or `eilen 12:34`.
+    /// Parse timestamp of an item listing (when it was posted), relative to the fetch time
+    /// given to [new](Parser::new). See [DateParser::parse] for the supported formats.
+    /// Whitespace is normalized first, the same way as for timestamps found in a document.
     pub fn parse_posted_at(&self, ts: &str) -> DateParseResult<DateTime<Utc>> {
-        if let Some(patts) = REL_TIME.captures(ts) {
-            let (_, [relday_s, hhmm_s]) = patts.extract();
-            self.parse_rel_time(relday_s, hhmm_s)
-        } else if let Some(patts) = ABS_TIME.captures(ts) {
-            let (_, [day_s, month_s, hhmm_s]) = patts.extract();
-            self.parse_abs_time(day_s, month_s, hhmm_s)
-        } else {
-            Err(DateParseError::InvalidHighlevelStructure(ts.to_string()))
+        self.dates.parse(&reformat_ws(ts))
+    }
+
+    /// Like [parse_document](Parser::parse_document), but fails with
+    /// [NoRowsFound](ItemParseErrorKind::NoRowsFound) if the document looks like a results
+    /// page yet no rows match. Zero items on a results page usually means the markup has
+    /// changed rather than that the search had no hits, unless the page says so; see
+    /// [is_empty_results](Parser::is_empty_results).
+    pub fn parse_strict(&self, doc: &Html) -> ItemParseResult<Vec<Item>> {
+        if self.looks_like_results_page(doc)
+            && doc.select(&self.selectors.row).next().is_none()
+            && !self.is_empty_results(doc)
+        {
+            return Err(ItemParseError {
+                item_idx: 0,
+                item_id: None,
+                error: ItemParseErrorKind::NoRowsFound,
+                context: None,
+            });
         }
+        self.parse_document(doc)
     }
 
     /// Parses the entire document and retuns a vector of Items for later use.
+    /// With the `tracing` feature, the call is wrapped in a `parse_document` span.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn parse_document(&self, doc: &Html) -> ItemParseResult<Vec<Item>> {
-        let mut items = vec![];
-        use ItemParseErrorKind::*;
+        let result: ItemParseResult<Vec<Item>> = doc
+            .select(&self.selectors.row)
+            .enumerate()
+            .map(|(i, element)| self.parse_row(i, element))
+            .collect();
+        #[cfg(feature = "tracing")]
+        if let Ok(items) = &result {
+            tracing::debug!(items = items.len(), "parsed document");
+        }
+        result
+    }
 
-        for (i, element) in doc.select(&ROW_SELECTOR).enumerate() {
-            let item_id = {
-                let item_id = element.attr("id").ok_or(ItemParseError {
-                    item_idx: i,
-                    item_id: None,
-                    error: MissingAttribute(ItemAttribute::ID),
-                })?;
-
-                item_id
-                    .strip_prefix("item_")
-                    .ok_or(ItemParseError {
-                        item_idx: i,
-                        item_id: None,
-                        error: UnexpectedValue(ItemAttribute::ID, item_id.to_string()),
-                    })?
-                    .to_string()
-            };
-            let is_company_ad = {
-                let s = element.attr("data-company-ad").ok_or(ItemParseError {
-                    item_idx: i,
-                    item_id: Some(item_id.clone()),
-                    error: MissingAttribute(ItemAttribute::CompanyAd),
-                })?;
-
-                match s {
-                    "0" => Ok(false),
-                    "1" => Ok(true),
-                    _ => Err(ItemParseError {
-                        item_idx: i,
-                        item_id: Some(item_id.clone()),
-                        error: UnexpectedValue(ItemAttribute::CompanyAd, s.to_string()),
-                    }),
-                }
-            }?;
+    /// Like [parse_document](Parser::parse_document), but parses at most the first `limit`
+    /// rows, leaving the rest of the document untouched. Errors in those rows fail the
+    /// whole call as usual.
+    pub fn parse_document_limited(&self, doc: &Html, limit: usize) -> ItemParseResult<Vec<Item>> {
+        doc.select(&self.selectors.row)
+            .enumerate()
+            .take(limit)
+            .map(|(i, element)| self.parse_row(i, element))
+            .collect()
+    }
 
-            let href = element
-                .attr("href")
-                .map(|s| s.to_string())
-                .ok_or(ItemParseError {
-                    item_idx: i,
-                    item_id: Some(item_id.clone()),
-                    error: MissingAttribute(ItemAttribute::Href),
-                })?;
-
-            let price = {
-                let price_maybe = element
-                    .select(&PRICE_SELECTOR)
-                    .next()
-                    .map(|n| n.text().collect::<String>())
-                    .filter(|s| !s.is_empty());
-
-                // we need this rigamarole because we want to distinguish between
-                // a) having no price b) having valid/expected price c) having invalid/unexpected price string.
-                match price_maybe {
-                    None => None,
-                    Some(price_s) => {
-                        Some(price_parse(&price_s).map_err(|kind| ItemParseError {
-                            item_idx: i,
-                            item_id: Some(item_id.clone()),
-                            error: kind,
-                        })?)
-                    }
+    /// Like [parse_document](Parser::parse_document), but stops at the first item posted
+    /// before `cutoff`. Useful when polling: only items newer than the last seen one are
+    /// parsed. This assumes the results are ordered newest first, which is the default sort
+    /// order of tori.fi. Promoted items are shown on top regardless of their age, so they
+    /// never end the scan; those older than `cutoff` are left out.
+    pub fn parse_until(&self, doc: &Html, cutoff: DateTime<Utc>) -> ItemParseResult<Vec<Item>> {
+        let mut items = vec![];
+        for (i, element) in doc.select(&self.selectors.row).enumerate() {
+            let item = self.parse_row(i, element)?;
+            if item.posted_at < cutoff {
+                if item.is_promoted {
+                    continue;
                 }
-            };
+                break;
+            }
+            items.push(item);
+        }
+        Ok(items)
+    }
 
-            let thumbnail_url = element
-                .select(&IMAGE_SELECTOR)
-                .next()
-                .and_then(|n| n.attr("src"))
-                .map(|s| s.to_string());
+    /// Like [parse_document](Parser::parse_document), but rows that fail to parse are
+    /// skipped instead of aborting the whole document. The returned [ParseStats] tells how
+    /// many rows were seen and how many of them produced an item; zero rows seen in a
+    /// non-empty document usually means the page layout has changed.
+    pub fn parse_document_with_stats(&self, doc: &Html) -> (Vec<Item>, ParseStats) {
+        let mut stats = ParseStats::default();
 
-            let title = element
-                .select(&TITLE_SELECTOR)
-                .next()
-                .map(|s| s.inner_html())
+        let items = doc
+            .select(&self.selectors.row)
+            .enumerate()
+            .filter_map(|(i, element)| {
+                stats.rows_seen += 1;
+                self.parse_row(i, element).ok()
+            })
+            .collect::<Vec<Item>>();
+
+        stats.items_ok = items.len();
+
+        (items, stats)
+    }
+
+    /// Price of an item row. `None` if the row has no price element or it is blank,
+    /// otherwise the result of [price_parse] on its text. Text split across child elements,
+    /// such as `<span>50</span><span>€</span>`, is joined with spaces. Useful for composing
+    /// custom parsers; [parse_document](Parser::parse_document) uses this internally.
+    pub fn extract_price(&self, row: ElementRef) -> Option<Result<Price, ItemParseErrorKind>> {
+        row.select(&self.selectors.price)
+            .next()
+            .map(|n| reformat_ws(&n.text().collect::<Vec<&str>>().join(" ")))
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| price_parse_with(&s, self.number_format))
+    }
+
+    /// Raw posted-at text of an item row with whitespace normalized, e.g. `"tänään 12:34"`.
+    /// Pass it to [parse_posted_at](Parser::parse_posted_at) to resolve it.
+    pub fn extract_posted_at(&self, row: ElementRef) -> Option<String> {
+        row.select(&self.selectors.posted_at)
+            .next()
+            .map(posted_at_text)
+    }
+
+    /// Parses a single item row. `i` is the index of the row within the document. Errors
+    /// carry a snippet of the row as context, and are reported as `tracing` events if the
+    /// feature is enabled.
+    fn parse_row(&self, i: usize, element: ElementRef) -> ItemParseResult<Item> {
+        self.parse_row_fields(i, element).map_err(|e| {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(item_idx = i, error = ?e.error, "failed to parse row");
+            ItemParseError {
+                context: Some(element.html().chars().take(ERROR_CONTEXT_LEN).collect()),
+                ..e
+            }
+        })
+    }
+
+    fn parse_row_fields(&self, i: usize, element: ElementRef) -> ItemParseResult<Item> {
+        use ItemParseErrorKind::*;
+
+        let item_id = {
+            let item_id = element.attr("id").ok_or(ItemParseError {
+                item_idx: i,
+                item_id: None,
+                error: MissingAttribute(ItemAttribute::ID),
+                context: None,
+            })?;
+
+            item_id
+                .strip_prefix("item_")
                 .ok_or(ItemParseError {
                     item_idx: i,
-                    item_id: Some(item_id.clone()),
-                    error: MissingAttribute(ItemAttribute::Title),
+                    item_id: None,
+                    error: UnexpectedValue(ItemAttribute::ID, item_id.to_string()),
+                    context: None,
                 })?
-                .trim()
-                .to_string();
+                .to_string()
+        };
+        let is_company_ad = {
+            let s = element.attr("data-company-ad").ok_or(ItemParseError {
+                item_idx: i,
+                item_id: Some(item_id.clone()),
+                error: MissingAttribute(ItemAttribute::CompanyAd),
+                context: None,
+            })?;
 
-            let posted_at = element
-                .select(&POSTED_AT_SELECTOR)
-                .next()
-                .map(|s| reformat_ws(&s.inner_html()))
-                .ok_or(ItemParseError {
+            if self.company_ad_true.iter().any(|v| v == s) {
+                Ok(true)
+            } else if self.company_ad_false.iter().any(|v| v == s) {
+                Ok(false)
+            } else {
+                Err(ItemParseError {
                     item_idx: i,
                     item_id: Some(item_id.clone()),
-                    error: MissingAttribute(ItemAttribute::PostedAt),
-                })?;
+                    error: UnexpectedValue(ItemAttribute::CompanyAd, s.to_string()),
+                    context: None,
+                })
+            }
+        }?;
 
-            let posted_at_parsed =
-                self.parse_posted_at(&posted_at)
-                    .map_err(|e| ItemParseError {
-                        item_idx: i,
-                        item_id: Some(item_id.clone()),
-                        error: InvalidDate(e),
-                    })?;
+        let href = element
+            .attr("href")
+            .map(|s| s.to_string())
+            .ok_or(ItemParseError {
+                item_idx: i,
+                item_id: Some(item_id.clone()),
+                error: MissingAttribute(ItemAttribute::Href),
+                context: None,
+            })?;
 
-            let mut combined = element.select(&COMBINED_SELECTOR);
+        let price_present = element.select(&self.selectors.price).next().is_some();
 
-            let location = combined
-                .next()
-                .map(|n| reformat_ws(&n.inner_html()))
-                .ok_or(ItemParseError {
+        // we need this rigamarole because we want to distinguish between
+        // a) having no price b) having valid/expected price c) having some other text, such
+        // as "Sop.", which is kept verbatim rather than failing the row.
+        let (price, price_kind) = match self.extract_price(element) {
+            None => (None, None),
+            Some(Ok(price))
+                if self.treat_zero_as_none && price.value == 0 && price.max.is_none() =>
+            {
+                (None, None)
+            }
+            Some(Ok(price)) => (Some(price), Some(PriceKind::Fixed)),
+            Some(Err(InvalidPrice(text) | PriceUnitMismatch(text))) => {
+                (None, Some(PriceKind::from_text(&text)))
+            }
+            Some(Err(kind)) => {
+                return Err(ItemParseError {
                     item_idx: i,
                     item_id: Some(item_id.clone()),
-                    error: MissingAttribute(ItemAttribute::Location),
-                })?;
+                    error: kind,
+                    context: None,
+                })
+            }
+        };
 
-            let direction = combined
+        let thumbnail = element.select(&self.selectors.image).next();
+
+        let thumbnail_url = thumbnail.and_then(image_url).map(|s| s.to_string());
+
+        let thumbnail_srcset = thumbnail
+            .and_then(|n| n.attr("srcset"))
+            .map(parse_srcset)
+            .unwrap_or_default();
+
+        let mut all_thumbnails: Vec<String> = vec![];
+        for url in element.select(&self.selectors.image).filter_map(image_url) {
+            if !all_thumbnails.iter().any(|u| u == url) {
+                all_thumbnails.push(url.to_string());
+            }
+        }
+
+        let title_raw = element
+            .select(&self.selectors.title)
+            .next()
+            .map(|s| s.inner_html())
+            .ok_or(ItemParseError {
+                item_idx: i,
+                item_id: Some(item_id.clone()),
+                error: MissingAttribute(ItemAttribute::Title),
+                context: None,
+            })?
+            .trim()
+            .to_string();
+
+        let category = {
+            let title_text: String = element
+                .select(&self.selectors.title)
                 .next()
-                .map(|n| reformat_ws(&n.inner_html()))
-                .ok_or(ItemParseError {
-                    item_idx: i,
-                    item_id: Some(item_id.clone()),
-                    error: MissingAttribute(ItemAttribute::Direction),
-                })?;
-
-            let seller_maybe = {
-                let v: Vec<String> = combined.map(|n| reformat_ws(&n.inner_html())).collect();
-                match v.len() {
-                    0 => None,
-                    _ => Some(v.join(" ")),
+                .map(|n| n.text().collect())
+                .unwrap_or_default();
+            thumbnail
+                .and_then(|n| n.value().attr("alt"))
+                .and_then(|alt| category_from_alt(alt, &title_text))
+        };
+
+        let title = if self.clean_titles {
+            strip_title_suffix(&title_raw).to_string()
+        } else {
+            title_raw.clone()
+        };
+
+        // the teaser snippet is only present on some layouts; absence is not an error.
+        let description = element
+            .select(&self.selectors.description)
+            .next()
+            .map(|n| reformat_ws(&n.inner_html()))
+            .filter(|s| !s.is_empty());
+
+        let posted_at = self.extract_posted_at(element).ok_or(ItemParseError {
+            item_idx: i,
+            item_id: Some(item_id.clone()),
+            error: MissingAttribute(ItemAttribute::PostedAt),
+            context: None,
+        })?;
+
+        let posted_at_parsed = self
+            .parse_posted_at(&posted_at)
+            .map_err(|e| ItemParseError {
+                item_idx: i,
+                item_id: Some(item_id.clone()),
+                error: InvalidDate(e),
+                context: None,
+            })?;
+
+        // optional, and its selector is unverified; an unparseable bump time must not cost
+        // the whole row, so it is treated like a missing one.
+        let bumped_at = element
+            .select(&self.selectors.bumped_at)
+            .next()
+            .and_then(|node| self.parse_posted_at(&posted_at_text(node)).ok());
+
+        // the paragraphs are usually ordered location, direction, seller; but recognize a known
+        // direction phrase by content instead of position, so that reordered or omitted
+        // paragraphs do not silently end up in the wrong field. Unknown phrases fall back to
+        // position: the second non-seller paragraph is the direction.
+        let mut location = None;
+        let mut direction = None;
+        let mut seller_parts = vec![];
+        let mut seller_href = None;
+
+        let paragraphs: Vec<ElementRef> = element.select(&self.selectors.combined).collect();
+        let is_seller = |n: &ElementRef| n.value().classes().any(|c| c == SELLER_CLASS);
+        let direction_idx = paragraphs
+            .iter()
+            .position(|n| {
+                !is_seller(n)
+                    && !matches!(
+                        Direction::from_phrase(&reformat_ws(&n.inner_html())),
+                        Direction::Other(_)
+                    )
+            })
+            .or_else(|| {
+                paragraphs
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, n)| !is_seller(n))
+                    .nth(1)
+                    .map(|(j, _)| j)
+            });
+
+        for (j, n) in paragraphs.iter().enumerate() {
+            let text = reformat_ws(&n.inner_html());
+
+            if direction_idx == Some(j) {
+                direction = Some(text);
+            } else if location.is_none() && !is_seller(n) {
+                location = Some(text);
+            } else {
+                // the store name may be wrapped in a link to the seller's page
+                if seller_href.is_none() {
+                    seller_href = n
+                        .select(&LINK_SELECTOR)
+                        .next()
+                        .and_then(|a| a.value().attr("href"))
+                        .map(|href| self.resolve_url(href));
                 }
-            };
-
-            let item = Item {
-                item_id: item_id,
-                direction: direction,
-                title: title,
-                is_company_ad: is_company_ad,
-                href: href,
-                price: price,
-                thumbnail_url: thumbnail_url,
-                posted_at_orig: posted_at,
-                posted_at: posted_at_parsed,
-                location: location,
-                seller: seller_maybe,
-            };
+                seller_parts.push(reformat_ws(&n.text().collect::<String>()));
+            }
+        }
 
-            items.push(item);
+        let location = location.ok_or(ItemParseError {
+            item_idx: i,
+            item_id: Some(item_id.clone()),
+            error: MissingAttribute(ItemAttribute::Location),
+            context: None,
+        })?;
+
+        let direction = direction.ok_or(ItemParseError {
+            item_idx: i,
+            item_id: Some(item_id.clone()),
+            error: MissingAttribute(ItemAttribute::Direction),
+            context: None,
+        })?;
+
+        let seller_maybe = match seller_parts.len() {
+            0 => None,
+            _ => Some(seller_parts.join(" ")),
+        };
+
+        let coordinates = self
+            .geocoder
+            .as_ref()
+            .and_then(|geocode| geocode(&location));
+
+        Ok(Item {
+            item_id: item_id,
+            direction: direction,
+            title: title,
+            title_raw: title_raw,
+            description: description,
+            is_company_ad: is_company_ad,
+            is_promoted: element.select(&self.selectors.promoted).next().is_some(),
+            href: href,
+            price: price,
+            price_present: price_present,
+            price_kind: price_kind,
+            thumbnail_url: thumbnail_url,
+            thumbnail_srcset: thumbnail_srcset,
+            all_thumbnails: all_thumbnails,
+            posted_at_orig: posted_at,
+            posted_at: posted_at_parsed,
+            posted_at_local: posted_at_parsed.with_timezone(&self.dates.timezone()),
+            bumped_at: bumped_at,
+            raw_html: self.keep_raw_html.then(|| element.html()),
+            coordinates: coordinates,
+            location: location,
+            category: category,
+            seller: seller_maybe,
+            seller_href: seller_href,
+        })
+    }
+
+    /// Counts how many elements each configured selector matches in the document. This is a
+    /// diagnostics aid for finding out which selector fails when a page yields fewer items
+    /// than expected; the per-row selectors are counted across the whole document, not
+    /// only within rows.
+    pub fn debug_counts(&self, doc: &Html) -> SelectorCounts {
+        let count = |selector: &Selector| doc.select(selector).count();
+        SelectorCounts {
+            rows: count(&self.selectors.row),
+            titles: count(&self.selectors.title),
+            descriptions: count(&self.selectors.description),
+            prices: count(&self.selectors.price),
+            images: count(&self.selectors.image),
+            posted_at: count(&self.selectors.posted_at),
+            combined: count(&self.selectors.combined),
         }
-        Ok(items)
+    }
+
+    /// Checks whether the document looks like a search results page, i.e. contains the
+    /// results container. Use this to tell a genuinely empty search apart from e.g. a captcha
+    /// or an error page, both of which would otherwise parse to zero items.
+    pub fn looks_like_results_page(&self, doc: &Html) -> bool {
+        doc.select(&self.selectors.results).next().is_some()
+    }
+
+    /// True if the page shows the "no results" message and no rows, i.e. the search genuinely
+    /// had no hits. Use this to tell an empty result of
+    /// [parse_document](Parser::parse_document) apart from a page the parser did not
+    /// understand.
+    pub fn is_empty_results(&self, doc: &Html) -> bool {
+        doc.select(&self.selectors.empty_results).next().is_some()
+            && doc.select(&self.selectors.row).next().is_none()
+    }
+
+    /// The region the search was limited to, as shown in the page's search form, e.g.
+    /// `"Koko Suomi"` (whole Finland) or `"Uusimaa"`. `None` if the page has no search form.
+    pub fn parse_active_region(&self, doc: &Html) -> Option<String> {
+        doc.select(&self.selectors.active_region)
+            .next()
+            .map(|n| reformat_ws(&n.text().collect::<String>()))
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Collects only the item ids found in the document, skipping rows without a valid id.
+    /// Much cheaper than [parse_document](Parser::parse_document) when only the set of
+    /// listings matters (e.g. for change detection).
+    pub fn parse_ids(&self, doc: &Html) -> Vec<String> {
+        doc.select(&self.selectors.row)
+            .filter_map(|element| element.attr("id"))
+            .filter_map(|id| id.strip_prefix("item_"))
+            .map(|id| id.to_string())
+            .collect()
+    }
+
+    /// Collects the `href` of every row in the document, skipping only rows without one.
+    /// Unlike [parse_document](Parser::parse_document), rows that are otherwise broken
+    /// (e.g. missing a title) are included.
+    pub fn parse_hrefs(&self, doc: &Html) -> Vec<String> {
+        doc.select(&self.selectors.row)
+            .filter_map(|element| element.attr("href"))
+            .map(|href| href.to_string())
+            .collect()
     }
 
     /// Convenience function for parsing items from a string buffer. Calls parse_document()
-    /// internally.
+    /// internally; prefer parsing the [Html] yourself if you need to query the same page
+    /// more than once. Since the HTML parser accepts anything, input that does not start
+    /// with a tag (such as a JSON error body or binary data) is rejected with
+    /// [NotHtml](ItemParseErrorKind::NotHtml) rather than silently yielding zero items.
     pub fn parse_from_string(&self, buf: &str) -> ItemParseResult<Vec<Item>> {
+        if !looks_like_html(buf) {
+            return Err(ItemParseError {
+                item_idx: 0,
+                item_id: None,
+                error: ItemParseErrorKind::NotHtml,
+                context: Some(buf.chars().take(ERROR_CONTEXT_LEN).collect()),
+            });
+        }
         let doc = Html::parse_document(buf);
         self.parse_document(&doc)
     }
+
+    /// Decodes `bytes` (e.g. an HTTP response body) from the given encoding and parses the
+    /// result, see [parse_from_string](Parser::parse_from_string). Malformed sequences are
+    /// replaced with U+FFFD.
+    pub fn parse_bytes(
+        &self,
+        bytes: &[u8],
+        encoding: &'static Encoding,
+    ) -> ItemParseResult<Vec<Item>> {
+        let (buf, _, _) = encoding.decode(bytes);
+        self.parse_from_string(&buf)
+    }
 }
 
 #[cfg(test)]
@@ -429,49 +1470,1397 @@ mod tests {
         }
     }
 
+    /// Minimal search result row mimicking the structure of the bundled dumps. Older tests
+    /// derive variants from it with `str::replace`; new tests should build rows with
+    /// [RowBuilder](crate::testutil::RowBuilder) instead.
+    const SYNTHETIC_ROW: &str = r#"
+<a id="item_123" href="https://www.tori.fi/uusimaa/Sohva_123.htm?ca=18&w=3" class="item_row_flex" data-row="1" data-company-ad="0">
+    <div class="image_container">
+        <div class="item_image_div"><img class="item_image" src="https://images.tori.fi/api/v1/imagestori/images/1.jpg?rule=thumb_280x210" /></div>
+    </div>
+    <div class="desc_flex">
+        <div class="ad-details-left">
+            <div class="li-title">Sohva</div>
+            <div class="list-details-container">
+                <p class="list_price ineuros">50 &euro;</p>
+            </div>
+        </div>
+        <div class="ad-details-right">
+            <div class="date-cat-container">
+                <div class="date_image">tänään 12:34</div>
+                <div class="cat_geo clean_links">
+                    <p>Uusimaa</p>
+                    <p>Myydään</p>
+                </div>
+            </div>
+        </div>
+    </div>
+</a>
+"#;
+
+    fn parse_synthetic(buf: &str) -> Vec<Item> {
+        Parser::new(get_time()).parse_from_string(buf).unwrap()
+    }
+
     #[test]
-    fn test_parse_price() {
-        assert_eq!(
-            price_parse("1 €"),
-            Ok(Price {
-                value: 1,
-                unit: "€".to_string(),
-            })
+    fn test_parse_description() {
+        let items = parse_synthetic(SYNTHETIC_ROW);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].description, None);
+
+        let buf = SYNTHETIC_ROW.replace(
+            r#"<div class="li-title">Sohva</div>"#,
+            r#"<div class="li-title">Sohva</div>
+            <p class="li-description">  Hyvä   kunto,
+                noudettava  </p>"#,
         );
+        let items = parse_synthetic(&buf);
         assert_eq!(
-            price_parse(" 1 599  €"),
-            Ok(Price {
+            items[0].description,
+            Some("Hyvä kunto, noudettava".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_error_context() {
+        let buf = SYNTHETIC_ROW.replace(r#"data-company-ad="0""#, r#"data-company-ad="x""#);
+        let err = Parser::new(get_time()).parse_from_string(&buf).unwrap_err();
+        let context = err.context.unwrap();
+        assert!(context.contains("item_123"));
+        assert!(context.starts_with("<a "));
+        assert!(context.chars().count() <= ERROR_CONTEXT_LEN);
+    }
+
+    #[test]
+    fn test_parse_cat_geo() {
+        let cat_geo = |paragraphs: &str| {
+            SYNTHETIC_ROW.replace(
+                "<p>Uusimaa</p>\n                    <p>Myydään</p>",
+                paragraphs,
+            )
+        };
+        assert_ne!(cat_geo("foo"), SYNTHETIC_ROW);
+
+        // 1 paragraph
+        let result = Parser::new(get_time()).parse_from_string(&cat_geo("<p>Uusimaa</p>"));
+        assert_eq!(
+            result.unwrap_err().error,
+            ItemParseErrorKind::MissingAttribute(ItemAttribute::Direction)
+        );
+        let result = Parser::new(get_time()).parse_from_string(&cat_geo("<p>Myydään</p>"));
+        assert_eq!(
+            result.unwrap_err().error,
+            ItemParseErrorKind::MissingAttribute(ItemAttribute::Location)
+        );
+
+        // 2 paragraphs, in either order
+        for paragraphs in [
+            "<p>Uusimaa</p><p>Ostetaan</p>",
+            "<p>Ostetaan</p><p>Uusimaa</p>",
+        ] {
+            let items = parse_synthetic(&cat_geo(paragraphs));
+            assert_eq!(items[0].location, "Uusimaa");
+            assert_eq!(items[0].direction, "Ostetaan");
+            assert_eq!(items[0].seller, None);
+        }
+
+        // 3 paragraphs
+        let items = parse_synthetic(&cat_geo(
+            r#"<p class="list_store_name">DigiAura</p><p>Uusimaa</p><p>Myydään</p>"#,
+        ));
+        assert_eq!(items[0].location, "Uusimaa");
+        assert_eq!(items[0].direction, "Myydään");
+        assert_eq!(items[0].seller, Some("DigiAura".to_string()));
+    }
+
+    #[test]
+    fn test_parse_unknown_direction() {
+        use crate::testutil::RowBuilder;
+
+        let parser = Parser::new(get_time());
+        let doc = Html::parse_document(
+            &RowBuilder::new()
+                .cat_geo(&["Uusimaa", "Vaihdetaan"])
+                .build(),
+        );
+        let items = parser.parse_document(&doc).unwrap();
+        assert_eq!(items[0].location, "Uusimaa");
+        assert_eq!(items[0].direction, "Vaihdetaan");
+        assert_eq!(
+            items[0].direction_kind(),
+            Direction::Other("Vaihdetaan".to_string())
+        );
+        assert_eq!(items[0].seller, None);
+
+        let doc = Html::parse_document(
+            &RowBuilder::new()
+                .cat_geo(&["Espoo", "Vaihdetaan", "Huonekalu Oy"])
+                .build(),
+        );
+        let items = parser.parse_document(&doc).unwrap();
+        assert_eq!(items[0].location, "Espoo");
+        assert_eq!(items[0].direction, "Vaihdetaan");
+        assert_eq!(items[0].seller, Some("Huonekalu Oy".to_string()));
+    }
+
+    #[test]
+    fn test_parse_seller_href() {
+        use crate::testutil::RowBuilder;
+
+        let buf = RowBuilder::new()
+            .company_ad("1")
+            .cat_geo(&[
+                "Espoo",
+                "Myydään",
+                r#"<a href="/yritys/huonekalu-oy">Huonekalu Oy</a>"#,
+            ])
+            .build();
+        let items = Parser::new(get_time()).parse_from_string(&buf).unwrap();
+        assert_eq!(items[0].seller, Some("Huonekalu Oy".to_string()));
+        assert_eq!(
+            items[0].seller_href,
+            Some("/yritys/huonekalu-oy".to_string())
+        );
+
+        let items = Parser::new(get_time())
+            .with_base_url(Url::parse("https://www.tori.fi/uusimaa?q=sohva").unwrap())
+            .parse_from_string(&buf)
+            .unwrap();
+        assert_eq!(
+            items[0].seller_href,
+            Some("https://www.tori.fi/yritys/huonekalu-oy".to_string())
+        );
+
+        let buf = RowBuilder::new()
+            .cat_geo(&["Espoo", "Myydään", "Huonekalu Oy"])
+            .build();
+        let items = Parser::new(get_time()).parse_from_string(&buf).unwrap();
+        assert_eq!(items[0].seller_href, None);
+    }
+
+    #[test]
+    fn test_item_age() {
+        let now = get_time().with_timezone(&Utc);
+        let mut item = parse_synthetic(SYNTHETIC_ROW).remove(0);
+
+        item.posted_at = now - Duration::hours(1);
+        assert_eq!(item.age(now), Duration::hours(1));
+        assert!(item.is_fresh(now, Duration::hours(2)));
+        assert!(item.is_fresh(now, Duration::hours(1)));
+
+        item.posted_at = now - Duration::weeks(1);
+        assert_eq!(item.age(now), Duration::days(7));
+        assert!(!item.is_fresh(now, Duration::days(1)));
+    }
+
+    #[test]
+    fn test_price_from_title() {
+        let mut item = parse_synthetic(SYNTHETIC_ROW).remove(0);
+        let price_from_title = |item: &Item| item.price_from_title().map(|p| p.value);
+
+        item.title = "Sohva 50 €".to_string();
+        assert_eq!(price_from_title(&item), Some(50));
+        item.title = "Sohva 50€".to_string();
+        assert_eq!(price_from_title(&item), Some(50));
+        item.title = "Sohva 1 200 €, hyvä kunto".to_string();
+        assert_eq!(price_from_title(&item), Some(1200));
+        item.title = "Sohva, hyvä kunto".to_string();
+        assert_eq!(price_from_title(&item), None);
+        item.title = "Golf 2005 TDI".to_string();
+        assert_eq!(price_from_title(&item), None);
+    }
+
+    #[test]
+    fn test_item_validate() {
+        let now = get_time().with_timezone(&Utc);
+        let mut item = parse_synthetic(SYNTHETIC_ROW).remove(0);
+        // the synthetic row is posted later today than the fetch time
+        assert_eq!(item.validate(now), vec![ItemWarning::PostedInFuture]);
+        item.posted_at = now - Duration::hours(1);
+        assert!(item.validate(now).is_empty());
+
+        let mut broken = item.clone();
+        broken.title = "  ".to_string();
+        assert_eq!(broken.validate(now), vec![ItemWarning::EmptyTitle]);
+
+        let mut broken = item.clone();
+        broken.price.as_mut().unwrap().value = 0;
+        assert_eq!(broken.validate(now), vec![ItemWarning::ZeroPrice]);
+
+        let mut broken = item.clone();
+        broken.posted_at = now + Duration::days(30);
+        assert_eq!(broken.validate(now), vec![ItemWarning::PostedInFuture]);
+        broken.posted_at = now + Duration::minutes(1);
+        assert!(broken.validate(now).is_empty());
+    }
+
+    #[test]
+    fn test_item_validate_today_after_fetch_time() {
+        use crate::testutil::RowBuilder;
+
+        let fetch_time = get_time()
+            .timezone()
+            .with_ymd_and_hms(2023, 3, 25, 0, 10, 0)
+            .unwrap();
+        let buf = RowBuilder::new().posted_at("tänään 23:50").build();
+        let items = Parser::new(fetch_time).parse_from_string(&buf).unwrap();
+
+        let fetch_time = fetch_time.with_timezone(&Utc);
+        assert!(items[0].posted_at > fetch_time);
+        assert_eq!(
+            items[0].validate(fetch_time),
+            vec![ItemWarning::PostedInFuture]
+        );
+    }
+
+    #[test]
+    fn test_direction_kind() {
+        assert_eq!(Direction::from_phrase("Myydään"), Direction::Selling);
+        assert_eq!(Direction::from_phrase("Ostetaan"), Direction::Buying);
+        assert_eq!(Direction::from_phrase("Vuokrataan"), Direction::Renting);
+        assert_eq!(
+            Direction::from_phrase("Halutaan vuokrata"),
+            Direction::WantToRent
+        );
+        assert_eq!(Direction::from_phrase("Annetaan"), Direction::Giving);
+        assert_eq!(
+            Direction::from_phrase("Vaihdetaan"),
+            Direction::Other("Vaihdetaan".to_string())
+        );
+
+        let items = parse_synthetic(SYNTHETIC_ROW);
+        assert_eq!(items[0].direction, "Myydään");
+        assert_eq!(items[0].direction_kind(), Direction::Selling);
+    }
+
+    #[test]
+    fn test_parse_posted_at_img_alt() {
+        use crate::testutil::RowBuilder;
+
+        let buf = RowBuilder::new()
+            .posted_at(r#"<img src="/img/date.png" alt="tänään 12:34" />"#)
+            .build();
+        let items = parse_synthetic(&buf);
+        assert_eq!(items[0].posted_at_orig, "tänään 12:34");
+        assert_eq!(
+            items[0].posted_at,
+            parse_synthetic(&RowBuilder::new().posted_at("tänään 12:34").build())[0].posted_at
+        );
+
+        let buf = RowBuilder::new()
+            .posted_at(r#"<img title="eilen 08:00" />"#)
+            .build();
+        assert_eq!(parse_synthetic(&buf)[0].posted_at_orig, "eilen 08:00");
+    }
+
+    #[test]
+    fn test_parse_giveaway() {
+        use crate::testutil::RowBuilder;
+
+        let row = || RowBuilder::new().cat_geo(&["Uusimaa", "Annetaan"]);
+
+        let items = parse_synthetic(&row().price("").build());
+        assert_eq!(items[0].direction_kind(), Direction::Giving);
+        assert!(items[0].price_present);
+        assert_eq!(items[0].price, None);
+
+        let items = parse_synthetic(&row().price("0 &euro;").build());
+        assert_eq!(items[0].direction_kind(), Direction::Giving);
+        assert_eq!(items[0].price.as_ref().map(|p| p.value), Some(0));
+
+        let items = parse_synthetic(&row().no_price().build());
+        assert_eq!(items[0].direction_kind(), Direction::Giving);
+        assert!(!items[0].price_present);
+    }
+
+    #[test]
+    fn test_geocoder() {
+        use crate::testutil::RowBuilder;
+
+        let buf: String = ["Helsinki", "Tampere"]
+            .iter()
+            .map(|location| {
+                RowBuilder::new()
+                    .id(location)
+                    .cat_geo(&[location, "Myydään"])
+                    .build()
+            })
+            .collect();
+
+        let items = Parser::new(get_time()).parse_from_string(&buf).unwrap();
+        assert!(items.iter().all(|item| item.coordinates.is_none()));
+        assert_eq!(items[0].to_geojson_feature(), None);
+
+        let parser = Parser::new(get_time()).with_geocoder(|location| match location {
+            "Helsinki" => Some((60.1699, 24.9384)),
+            _ => None,
+        });
+        let items = parser.parse_from_string(&buf).unwrap();
+        assert_eq!(items[0].coordinates, Some((60.1699, 24.9384)));
+        assert_eq!(items[1].coordinates, None);
+
+        let feature = items[0].to_geojson_feature().unwrap();
+        assert_eq!(feature["type"], "Feature");
+        assert_eq!(
+            feature["geometry"]["coordinates"],
+            json!([24.9384, 60.1699])
+        );
+        assert_eq!(feature["properties"]["location"], "Helsinki");
+    }
+
+    #[test]
+    fn test_keep_raw_html() {
+        let items = parse_synthetic(SYNTHETIC_ROW);
+        assert_eq!(items[0].raw_html, None);
+
+        let items = Parser::new(get_time())
+            .keep_raw_html(true)
+            .parse_from_string(SYNTHETIC_ROW)
+            .unwrap();
+        let raw_html = items[0].raw_html.as_ref().unwrap();
+        // attribute order is not preserved
+        assert!(raw_html.starts_with("<a "));
+        assert!(raw_html.contains(r#"id="item_123""#));
+        assert!(raw_html.contains("tänään 12:34"));
+        assert!(raw_html.ends_with("</a>"));
+    }
+
+    #[test]
+    fn test_parse_bumped_at() {
+        use crate::testutil::RowBuilder;
+
+        assert_eq!(
+            parse_synthetic(&RowBuilder::new().build())[0].bumped_at,
+            None
+        );
+
+        let buf = RowBuilder::new()
+            .posted_at("21 maa 09:00")
+            .bumped_at("tänään 08:15")
+            .build();
+        let item = parse_synthetic(&buf).remove(0);
+        let helsinki = chrono_tz::Europe::Helsinki;
+        assert_eq!(
+            item.posted_at,
+            helsinki
+                .with_ymd_and_hms(2023, 3, 21, 9, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc)
+        );
+        assert_eq!(
+            item.bumped_at,
+            Some(
+                helsinki
+                    .with_ymd_and_hms(2023, 3, 25, 8, 15, 0)
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )
+        );
+
+        // an unparseable bump time does not fail the row
+        let buf = buf.replace("tänään 08:15", "huomenna 08:15");
+        let item = parse_synthetic(&buf).remove(0);
+        assert_eq!(item.bumped_at, None);
+        assert_eq!(item.posted_at_orig, "21 maa 09:00");
+    }
+
+    #[test]
+    fn test_company_ad_values() {
+        use crate::testutil::RowBuilder;
+
+        let parser = Parser::new(get_time()).company_ad_values(&["yes"], &["no"]);
+        let buf = RowBuilder::new().company_ad("yes").build();
+        assert!(parser.parse_from_string(&buf).unwrap()[0].is_company_ad);
+        let buf = RowBuilder::new().company_ad("no").build();
+        assert!(!parser.parse_from_string(&buf).unwrap()[0].is_company_ad);
+
+        // the defaults are replaced, not extended
+        let buf = RowBuilder::new().company_ad("1").build();
+        assert_eq!(
+            parser.parse_from_string(&buf).unwrap_err().error,
+            ItemParseErrorKind::UnexpectedValue(ItemAttribute::CompanyAd, "1".to_string())
+        );
+
+        let parser = Parser::new(get_time()).company_ad_values(&["1", "true"], &["0", "false"]);
+        let buf = RowBuilder::new().company_ad("true").build();
+        assert!(parser.parse_from_string(&buf).unwrap()[0].is_company_ad);
+    }
+
+    #[test]
+    fn test_content_fingerprint() {
+        let item = parse_synthetic(SYNTHETIC_ROW).remove(0);
+        let fingerprint = item.content_fingerprint();
+        assert_eq!(fingerprint, item.clone().content_fingerprint());
+
+        let mut changed = item.clone();
+        changed.posted_at = changed.posted_at - Duration::days(1);
+        changed.posted_at_orig = "eilen 12:34".to_string();
+        assert_eq!(changed.content_fingerprint(), fingerprint);
+
+        let mut changed = item.clone();
+        changed.price.as_mut().unwrap().value = 45;
+        assert_ne!(changed.content_fingerprint(), fingerprint);
+
+        let mut changed = item.clone();
+        changed.title = "Sohva, hyvä kunto".to_string();
+        assert_ne!(changed.content_fingerprint(), fingerprint);
+
+        // missing and empty seller differ
+        let mut changed = item.clone();
+        changed.seller = Some(String::new());
+        assert_ne!(changed.content_fingerprint(), fingerprint);
+    }
+
+    #[test]
+    fn test_canonical_href() {
+        let mut item = parse_synthetic(SYNTHETIC_ROW).remove(0);
+        assert_eq!(
+            item.canonical_href(),
+            "https://www.tori.fi/uusimaa/Sohva_123.htm"
+        );
+
+        item.href = "https://www.tori.fi/uusimaa/Sohva_123.htm?utm_source=foo&ca=18".to_string();
+        assert_eq!(
+            item.canonical_href(),
+            "https://www.tori.fi/uusimaa/Sohva_123.htm"
+        );
+
+        item.href = "https://www.tori.fi/uusimaa/Sohva_123.htm?utm_medium=x&page=2".to_string();
+        assert_eq!(
+            item.canonical_href(),
+            "https://www.tori.fi/uusimaa/Sohva_123.htm?page=2"
+        );
+
+        item.href = "https://autot.tori.fi/motot/myydaan/mopot/110625137".to_string();
+        assert_eq!(item.canonical_href(), item.href);
+
+        item.href = "/relative/123.htm".to_string();
+        assert_eq!(item.canonical_href(), item.href);
+    }
+
+    #[test]
+    fn test_posted_at_was_relative() {
+        let items = parse_synthetic(SYNTHETIC_ROW);
+        assert_eq!(items[0].posted_at_orig, "tänään 12:34");
+        assert!(items[0].posted_at_was_relative());
+
+        let items = parse_synthetic(&SYNTHETIC_ROW.replace("tänään 12:34", "21 huh 19:52"));
+        assert_eq!(items[0].posted_at_orig, "21 huh 19:52");
+        assert!(!items[0].posted_at_was_relative());
+    }
+
+    #[test]
+    fn test_clean_titles() {
+        let buf = SYNTHETIC_ROW.replace(">Sohva<", ">Sohva | Huonekalut<");
+
+        let items = parse_synthetic(&buf);
+        assert_eq!(items[0].title, "Sohva | Huonekalut");
+        assert_eq!(items[0].title_raw, "Sohva | Huonekalut");
+
+        let parser = Parser::new(get_time()).clean_titles(true);
+        let items = parser.parse_from_string(&buf).unwrap();
+        assert_eq!(items[0].title, "Sohva");
+        assert_eq!(items[0].title_raw, "Sohva | Huonekalut");
+
+        let buf = SYNTHETIC_ROW.replace(">Sohva<", ">Sohva - Huonekalut<");
+        let items = parser.parse_from_string(&buf).unwrap();
+        assert_eq!(items[0].title, "Sohva");
+
+        let items = parser.parse_from_string(SYNTHETIC_ROW).unwrap();
+        assert_eq!(items[0].title, "Sohva");
+        assert_eq!(items[0].title_raw, "Sohva");
+
+        assert_eq!(strip_title_suffix("Sohva-pöytä"), "Sohva-pöytä");
+        assert_eq!(
+            strip_title_suffix("Sohva - 3 istuttava | Huonekalut"),
+            "Sohva - 3 istuttava"
+        );
+    }
+
+    #[test]
+    fn test_parse_promoted() {
+        let items = parse_synthetic(SYNTHETIC_ROW);
+        assert!(!items[0].is_promoted);
+
+        let buf = SYNTHETIC_ROW.replace(
+            r#"class="date-cat-container""#,
+            r#"class="date-cat-container bumped_date_cat""#,
+        );
+        let items = parse_synthetic(&buf);
+        assert!(items[0].is_promoted);
+    }
+
+    #[test]
+    fn test_posted_at_local() {
+        let items = parse_synthetic(SYNTHETIC_ROW);
+        assert_eq!(
+            items[0].posted_at_local,
+            chrono_tz::Europe::Helsinki
+                .with_ymd_and_hms(2023, 3, 25, 12, 34, 0)
+                .unwrap()
+        );
+        assert_eq!(
+            items[0].posted_at_local.timezone(),
+            chrono_tz::Europe::Helsinki
+        );
+        assert_eq!(items[0].posted_at_local, items[0].posted_at);
+    }
+
+    #[test]
+    fn test_parse_with_selectors() {
+        let buf = SYNTHETIC_ROW.replace("li-title", "li-heading");
+
+        let default_parser = Parser::new(get_time());
+        let result = default_parser.parse_from_string(&buf);
+        assert_eq!(
+            result.unwrap_err().error,
+            ItemParseErrorKind::MissingAttribute(ItemAttribute::Title)
+        );
+
+        let custom_parser = Parser::with_selectors(
+            get_time(),
+            Selectors {
+                title: Selector::parse("div .li-heading").unwrap(),
+                ..Selectors::default()
+            },
+        );
+        let items = custom_parser.parse_from_string(&buf).unwrap();
+        assert_eq!(items[0].title, "Sohva");
+    }
+
+    #[test]
+    fn test_parse_price_presence() {
+        let items = parse_synthetic(SYNTHETIC_ROW);
+        assert!(items[0].price_present);
+        assert_eq!(
+            items[0].price,
+            Some(Price {
+                value: 50,
+                unit: "€".to_string(),
+                per: None,
+                max: None,
+            })
+        );
+
+        let buf = SYNTHETIC_ROW.replace(
+            r#"<p class="list_price ineuros">50 &euro;</p>"#,
+            r#"<p class="list_price ineuros">  </p>"#,
+        );
+        let items = parse_synthetic(&buf);
+        assert!(items[0].price_present);
+        assert_eq!(items[0].price, None);
+
+        let buf = SYNTHETIC_ROW.replace(r#"<p class="list_price ineuros">50 &euro;</p>"#, "");
+        let items = parse_synthetic(&buf);
+        assert!(!items[0].price_present);
+        assert_eq!(items[0].price, None);
+    }
+
+    fn read_dump(path: &str) -> String {
+        let parent = Path::new(file!()).parent().unwrap();
+        utils::decode_to_string(
+            &parent.join(path),
+            utils::encoding_lookup("ISO_8859_15").unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_parse_document_with_stats() {
+        let broken = SYNTHETIC_ROW
+            .replace(r#"id="item_123""#, r#"id="item_124""#)
+            .replace("tänään 12:34", "huomenna 12:34");
+        let buf = format!("{}{}", SYNTHETIC_ROW, broken);
+        let doc = Html::parse_document(&buf);
+
+        let (items, stats) = Parser::new(get_time()).parse_document_with_stats(&doc);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].item_id, "123");
+        assert_eq!(
+            stats,
+            ParseStats {
+                rows_seen: 2,
+                items_ok: 1,
+            }
+        );
+        assert_eq!(stats.rows_skipped(), 1);
+
+        let doc = Html::parse_document("<html><body><p>Hello</p></body></html>");
+        let (items, stats) = Parser::new(get_time()).parse_document_with_stats(&doc);
+        assert!(items.is_empty());
+        assert_eq!(stats.rows_seen, 0);
+    }
+
+    #[test]
+    fn test_parse_document_limited() {
+        use crate::testutil::RowBuilder;
+
+        let doc = Html::parse_document(&read_dump("testdata/2023-03-25-105201-dump.html"));
+        let parser = Parser::new(get_time());
+        let all = parser.parse_document(&doc).unwrap();
+        assert_eq!(all.len(), 40);
+
+        let items = parser.parse_document_limited(&doc, 5).unwrap();
+        assert_eq!(items.len(), 5);
+        let ids = |items: &[Item]| items.iter().map(|i| i.item_id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&items), ids(&all[..5]));
+
+        assert_eq!(parser.parse_document_limited(&doc, 100).unwrap().len(), 40);
+        assert!(parser.parse_document_limited(&doc, 0).unwrap().is_empty());
+
+        // rows past the limit are never parsed, so their errors go unnoticed
+        let broken = RowBuilder::new()
+            .id("124")
+            .posted_at("huomenna 12:34")
+            .build();
+        let doc = Html::parse_document(&format!("{}{}", RowBuilder::new().build(), broken));
+        assert_eq!(parser.parse_document_limited(&doc, 1).unwrap().len(), 1);
+        assert!(parser.parse_document_limited(&doc, 2).is_err());
+    }
+
+    #[test]
+    fn test_parse_until() {
+        let doc = Html::parse_document(&read_dump("testdata/2023-03-25-105201-dump.html"));
+        let parser = Parser::new(get_time());
+        let all = parser.parse_document(&doc).unwrap();
+
+        // the first row is a promoted item from february, the rest are from today
+        assert!(all[0].is_promoted);
+        let cutoff = get_time()
+            .timezone()
+            .with_ymd_and_hms(2023, 3, 25, 15, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        let items = parser.parse_until(&doc, cutoff).unwrap();
+        assert_eq!(items.len(), 15);
+        assert_eq!(items[0].item_id, all[1].item_id);
+        assert_eq!(items.last().unwrap().posted_at_orig, "tänään 15:05");
+        assert!(items.iter().all(|item| item.posted_at >= cutoff));
+
+        // a cutoff before all items parses the whole document
+        let cutoff = all.iter().map(|item| item.posted_at).min().unwrap();
+        assert_eq!(parser.parse_until(&doc, cutoff).unwrap().len(), all.len());
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_parse_gzip_file() {
+        let parent = Path::new(file!()).parent().unwrap();
+        let buf = utils::decode_gzip_to_string(
+            &parent.join("testdata/2023-03-25-105201-dump.html.gz"),
+            utils::encoding_lookup("ISO_8859_15").unwrap(),
+        );
+        assert_eq!(buf, read_dump("testdata/2023-03-25-105201-dump.html"));
+        let items = Parser::new(get_time()).parse_from_string(&buf).unwrap();
+        assert_eq!(items.len(), 40);
+    }
+
+    #[test]
+    fn test_parse_bytes() {
+        let parent = Path::new(file!()).parent().unwrap();
+        let bytes = fs::read(parent.join("testdata/2023-03-25-105201-dump.html")).unwrap();
+        let parser = Parser::new(get_time());
+
+        let items = parser
+            .parse_bytes(&bytes, utils::encoding_lookup("ISO_8859_15").unwrap())
+            .unwrap();
+        let expected = parser
+            .parse_from_string(&read_dump("testdata/2023-03-25-105201-dump.html"))
+            .unwrap();
+        assert_eq!(items.len(), expected.len());
+        assert_eq!(items[0].posted_at_orig, expected[0].posted_at_orig);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tracing_test::traced_test]
+    #[test]
+    fn test_parse_document_tracing() {
+        let doc = Html::parse_document(&read_dump("testdata/2023-03-25-105201-dump.html"));
+        Parser::new(get_time()).parse_document(&doc).unwrap();
+        assert!(logs_contain("parse_document"));
+        assert!(logs_contain("items=40"));
+
+        let buf = crate::testutil::RowBuilder::new().company_ad("x").build();
+        let doc = Html::parse_document(&buf);
+        assert!(Parser::new(get_time()).parse_document(&doc).is_err());
+        assert!(logs_contain("failed to parse row"));
+        assert!(logs_contain("item_idx=0"));
+    }
+
+    #[test]
+    fn test_multiple_queries_on_same_document() {
+        let doc = Html::parse_document(&read_dump("testdata/2023-03-25-105201-dump.html"));
+        let parser = Parser::new(get_time());
+
+        let items = parser.parse_document(&doc).unwrap();
+        let ids = parser.parse_ids(&doc);
+        let (_, stats) = parser.parse_document_with_stats(&doc);
+        let counts = parser.debug_counts(&doc);
+
+        assert_eq!(items.len(), 40);
+        assert_eq!(
+            items
+                .iter()
+                .map(|i| i.item_id.clone())
+                .collect::<Vec<String>>(),
+            ids
+        );
+        assert_eq!(stats.items_ok, 40);
+        assert_eq!(counts.rows, 40);
+    }
+
+    #[test]
+    fn test_debug_counts() {
+        let doc = Html::parse_document(&read_dump("testdata/2023-03-25-105201-dump.html"));
+        let counts = Parser::new(get_time()).debug_counts(&doc);
+        assert_eq!(counts.rows, 40);
+        assert!(counts.titles >= counts.rows);
+        assert!(counts.posted_at >= counts.rows);
+
+        let counts = Parser::new(get_time()).debug_counts(&Html::parse_document("<html></html>"));
+        assert_eq!(counts, SelectorCounts::default());
+    }
+
+    #[test]
+    fn test_field_extractors() {
+        use crate::testutil::RowBuilder;
+
+        let parser = Parser::new(get_time());
+        let fragment = Html::parse_fragment(&RowBuilder::new().price("50 &euro;").build());
+        let row = fragment.select(&parser.selectors.row).next().unwrap();
+
+        assert_eq!(
+            parser.extract_price(row),
+            Some(Ok(Price {
+                value: 50,
+                unit: "€".to_string(),
+                per: None,
+                max: None,
+            }))
+        );
+        assert_eq!(
+            parser.extract_posted_at(row),
+            Some("tänään 12:34".to_string())
+        );
+
+        let fragment = Html::parse_fragment(&RowBuilder::new().price("halpa").build());
+        let row = fragment.select(&parser.selectors.row).next().unwrap();
+        assert_eq!(
+            parser.extract_price(row),
+            Some(Err(ItemParseErrorKind::InvalidPrice("halpa".to_string())))
+        );
+
+        let fragment = Html::parse_fragment(&RowBuilder::new().price(" ").build());
+        let row = fragment.select(&parser.selectors.row).next().unwrap();
+        assert_eq!(parser.extract_price(row), None);
+    }
+
+    #[test]
+    fn test_parse_not_html() {
+        let parser = Parser::new(get_time());
+
+        let err = parser
+            .parse_from_string(r#"{"error": "rate limited"}"#)
+            .unwrap_err();
+        assert_eq!(err.error, ItemParseErrorKind::NotHtml);
+        assert_eq!(
+            err.context,
+            Some(r#"{"error": "rate limited"}"#.to_string())
+        );
+
+        let err = parser
+            .parse_from_string("\u{0}\u{1}PK\u{3}\u{4}")
+            .unwrap_err();
+        assert_eq!(err.error, ItemParseErrorKind::NotHtml);
+        assert_eq!(
+            parser.parse_from_string("").unwrap_err().error,
+            ItemParseErrorKind::NotHtml
+        );
+
+        assert!(parser
+            .parse_from_string("\u{FEFF}  <html></html>")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parse_strict() {
+        let parser = Parser::new(get_time());
+
+        let doc = Html::parse_document(
+            r#"<html><body><div class="list_mode_thumb"></div></body></html>"#,
+        );
+        assert!(parser.parse_document(&doc).unwrap().is_empty());
+        assert_eq!(
+            parser.parse_strict(&doc).unwrap_err().error,
+            ItemParseErrorKind::NoRowsFound
+        );
+
+        let doc = Html::parse_document(&read_dump("testdata/2023-03-25-105201-dump.html"));
+        assert_eq!(parser.parse_strict(&doc).unwrap().len(), 40);
+    }
+
+    #[test]
+    fn test_looks_like_results_page() {
+        let parser = Parser::new(get_time());
+        for path in [
+            "testdata/2023-03-25-105201-dump.html",
+            "testdata/2024-01-30-123020-dump.html",
+        ] {
+            let doc = Html::parse_document(&read_dump(path));
+            assert!(parser.looks_like_results_page(&doc), "{}", path);
+        }
+
+        let doc = Html::parse_document("<html></html>");
+        assert!(!parser.looks_like_results_page(&doc));
+    }
+
+    #[test]
+    fn test_is_empty_results() {
+        let parser = Parser::new(get_time());
+
+        let doc = Html::parse_document(
+            r#"<html><body><div class="list_mode_thumb">
+                <div class="no_results">Haku ei tuottanut tuloksia.</div>
+            </div></body></html>"#,
+        );
+        assert!(parser.is_empty_results(&doc));
+        assert!(parser.parse_document(&doc).unwrap().is_empty());
+        assert!(parser.parse_strict(&doc).unwrap().is_empty());
+
+        let doc = Html::parse_document(&read_dump("testdata/2023-03-25-105201-dump.html"));
+        assert!(!parser.is_empty_results(&doc));
+
+        let doc = Html::parse_document(
+            r#"<html><body><div class="list_mode_thumb"></div></body></html>"#,
+        );
+        assert!(!parser.is_empty_results(&doc));
+    }
+
+    #[test]
+    fn test_parse_active_region() {
+        let parser = Parser::new(get_time());
+        for path in [
+            "testdata/2023-03-25-105201-dump.html",
+            "testdata/2024-01-30-123020-dump.html",
+        ] {
+            let doc = Html::parse_document(&read_dump(path));
+            assert_eq!(
+                parser.parse_active_region(&doc),
+                Some("Koko Suomi".to_string()),
+                "{}",
+                path
+            );
+        }
+
+        let doc = Html::parse_document(SYNTHETIC_ROW);
+        assert_eq!(parser.parse_active_region(&doc), None);
+    }
+
+    #[test]
+    fn test_parse_ids() {
+        let doc = Html::parse_document(&read_dump("testdata/2023-03-25-105201-dump.html"));
+        let ids = Parser::new(get_time()).parse_ids(&doc);
+        assert_eq!(ids.len(), 40);
+        assert_eq!(ids[0], "110625254");
+
+        let buf = SYNTHETIC_ROW.replace(r#"id="item_123""#, r#"id="foo_123""#);
+        let ids = Parser::new(get_time()).parse_ids(&Html::parse_document(&buf));
+        assert!(ids.is_empty());
+    }
+
+    #[test]
+    fn test_price_parse_opt() {
+        assert_eq!(price_parse_opt(""), None);
+        assert_eq!(price_parse_opt("   "), None);
+        assert_eq!(price_parse_opt("Sop."), None);
+        assert_eq!(price_parse_opt("1,599 €"), None);
+        assert_eq!(price_parse_opt("50 €"), price_parse("50 €").ok());
+        assert_eq!(price_parse_opt("50 €").map(|p| p.value), Some(50));
+    }
+
+    #[test]
+    fn test_parse_hrefs() {
+        use crate::testutil::RowBuilder;
+
+        let doc = Html::parse_document(&read_dump("testdata/2023-03-25-105201-dump.html"));
+        let parser = Parser::new(get_time());
+        let hrefs = parser.parse_hrefs(&doc);
+        assert_eq!(hrefs.len(), 40);
+        let items = parser.parse_document(&doc).unwrap();
+        assert_eq!(hrefs[0], items[0].href);
+
+        // rows that fail to parse still yield their href; rows without one are skipped
+        let broken = RowBuilder::new().id("1").company_ad("x").build();
+        let no_href = RowBuilder::new().id("2").no_href().build();
+        let doc = Html::parse_document(&format!("{}{}", broken, no_href));
+        assert!(parser.parse_document(&doc).is_err());
+        assert_eq!(
+            parser.parse_hrefs(&doc),
+            vec!["https://www.tori.fi/uusimaa/1.htm"]
+        );
+    }
+
+    #[test]
+    fn test_parse_price() {
+        assert_eq!(
+            price_parse("1 €"),
+            Ok(Price {
+                value: 1,
+                unit: "€".to_string(),
+                per: None,
+                max: None,
+            })
+        );
+        assert_eq!(
+            price_parse(" 1 599  €"),
+            Ok(Price {
                 value: 1599,
                 unit: "€".to_string(),
+                per: None,
+                max: None,
+            })
+        );
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn test_parse_price_decimal() {
+        use rust_decimal::Decimal;
+
+        let price = Price::parse_decimal("19 900,50 €").unwrap();
+        assert_eq!(price.value, Decimal::new(1990050, 2));
+        assert_eq!(price.unit, "€");
+
+        for input in ["1 €", " 1 599  €", "500 €/kk"] {
+            let decimal = Price::parse_decimal(input).unwrap();
+            let integer = price_parse(input).unwrap();
+            assert_eq!(decimal.value, Decimal::from(integer.value));
+            assert_eq!(decimal.unit, integer.unit);
+            assert_eq!(decimal.per, integer.per);
+        }
+
+        assert!(Price::parse_decimal("hinta sopimuksen mukaan").is_err());
+    }
+
+    #[test]
+    fn test_parse_price_currencies() {
+        let parse = |input| price_parse(input).map(|p| (p.value, p.unit));
+        assert_eq!(parse("1 000 kr"), Ok((1000, "kr".to_string())));
+        assert_eq!(parse("50 $"), Ok((50, "$".to_string())));
+        assert_eq!(parse("75 £"), Ok((75, "£".to_string())));
+        assert_eq!(parse("15 000 SEK"), Ok((15000, "SEK".to_string())));
+        assert_eq!(parse("50 €"), Ok((50, "€".to_string())));
+        assert_eq!(parse("100–200 kr"), Ok((100, "kr".to_string())));
+        assert_eq!(
+            price_parse("500 €/kk").map(|p| p.per),
+            Ok(Some("kk".to_string()))
+        );
+        assert!(price_parse("50 kroner").is_err());
+        // only known ISO codes, not any three capital letters
+        assert!(price_parse("50 ALV").is_err());
+        assert!(price_parse("2 UUS").is_err());
+    }
+
+    #[test]
+    fn test_parse_price_number_format() {
+        assert_eq!(
+            price_parse_with("1,599 €", NumberFormat::Finnish),
+            Err(ItemParseErrorKind::InvalidPrice("1,599 €".to_string()))
+        );
+        assert_eq!(
+            price_parse_with("1,599 €", NumberFormat::Generic).map(|p| p.value),
+            Ok(1599)
+        );
+        assert_eq!(
+            price_parse_with("1 599 €", NumberFormat::Generic).map(|p| p.value),
+            Ok(1599)
+        );
+        assert_eq!(
+            price_parse("1,599 €"),
+            price_parse_with("1,599 €", NumberFormat::Finnish)
+        );
+
+        let buf = crate::testutil::RowBuilder::new()
+            .price("1,599 &euro;")
+            .build();
+        let parser = Parser::new(get_time());
+        let items = parser.parse_from_string(&buf).unwrap();
+        assert_eq!(items[0].price, None);
+        assert_eq!(
+            items[0].price_kind,
+            Some(PriceKind::Raw("1,599 €".to_string()))
+        );
+        let parser = Parser::new(get_time()).number_format(NumberFormat::Generic);
+        let items = parser.parse_from_string(&buf).unwrap();
+        assert_eq!(items[0].price.as_ref().map(|p| p.value), Some(1599));
+    }
+
+    #[test]
+    fn test_treat_zero_as_none() {
+        use crate::testutil::RowBuilder;
+
+        let buf = RowBuilder::new().price("0 &euro;").build();
+
+        let item = parse_synthetic(&buf).remove(0);
+        assert_eq!(item.price.as_ref().map(|p| p.value), Some(0));
+        assert_eq!(item.price_kind, Some(PriceKind::Fixed));
+
+        let parser = Parser::new(get_time()).treat_zero_as_none(true);
+        let item = parser.parse_from_string(&buf).unwrap().remove(0);
+        assert_eq!(item.price, None);
+        assert_eq!(item.price_kind, None);
+        assert!(item.price_present);
+
+        let buf = RowBuilder::new().price("0-100 &euro;").build();
+        let item = parser.parse_from_string(&buf).unwrap().remove(0);
+        assert_eq!(item.price.as_ref().and_then(|p| p.max), Some(100));
+
+        let buf = RowBuilder::new().price("50 &euro;").build();
+        let item = parser.parse_from_string(&buf).unwrap().remove(0);
+        assert_eq!(item.price.as_ref().map(|p| p.value), Some(50));
+    }
+
+    #[test]
+    fn test_parse_price_split_nodes() {
+        use crate::testutil::RowBuilder;
+
+        let fifty = Some(Price {
+            value: 50,
+            unit: "€".to_string(),
+            per: None,
+            max: None,
+        });
+        for markup in [
+            "<span>50</span><span>&euro;</span>",
+            "<span>50</span>&euro;",
+            "<b>50</b> <span class=\"unit\">&euro;</span>",
+        ] {
+            let items = parse_synthetic(&RowBuilder::new().price(markup).build());
+            assert_eq!(items[0].price, fifty, "{}", markup);
+        }
+
+        let buf = RowBuilder::new()
+            .price("<span>1 599</span><span>&euro;</span>")
+            .build();
+        assert_eq!(
+            parse_synthetic(&buf)[0].price.as_ref().map(|p| p.value),
+            Some(1599)
+        );
+    }
+
+    #[test]
+    fn test_parse_price_kind() {
+        use crate::testutil::RowBuilder;
+
+        let price_of =
+            |text: &str| parse_synthetic(&RowBuilder::new().price(text).build()).remove(0);
+
+        let item = price_of("50 &euro;");
+        assert_eq!(item.price_kind, Some(PriceKind::Fixed));
+        assert!(item.price.is_some());
+
+        for text in ["Sop.", "Tarjoa", "  sopimuksen   mukaan "] {
+            let item = price_of(text);
+            assert_eq!(item.price_kind, Some(PriceKind::Negotiable), "{}", text);
+            assert_eq!(item.price, None);
+            assert!(item.price_present);
+        }
+
+        let item = price_of("Kysy lisää");
+        assert_eq!(
+            item.price_kind,
+            Some(PriceKind::Raw("Kysy lisää".to_string()))
+        );
+        assert_eq!(item.price, None);
+
+        assert_eq!(price_of(" ").price_kind, None);
+        let buf = RowBuilder::new().no_price().build();
+        assert_eq!(parse_synthetic(&buf)[0].price_kind, None);
+    }
+
+    #[test]
+    fn test_parse_price_unit_first() {
+        let fifty = Ok(Price {
+            value: 50,
+            unit: "€".to_string(),
+            per: None,
+            max: None,
+        });
+        assert_eq!(price_parse("€ 50"), fifty);
+        assert_eq!(price_parse("€50"), fifty);
+        assert_eq!(price_parse("50 €"), fifty);
+        assert_eq!(price_parse("€ 1 599").map(|p| p.value), Ok(1599));
+    }
+
+    #[test]
+    fn test_parse_srcset() {
+        assert_eq!(
+            parse_srcset("https://a/1.jpg 320w,https://a/2.jpg   640w , https://a/3.jpg 2x"),
+            vec![
+                ("https://a/1.jpg".to_string(), 320),
+                ("https://a/2.jpg".to_string(), 640)
+            ]
+        );
+        assert!(parse_srcset("").is_empty());
+
+        let items = parse_synthetic(SYNTHETIC_ROW);
+        assert!(items[0].thumbnail_srcset.is_empty());
+
+        let buf = SYNTHETIC_ROW.replace(
+            r#"<img class="item_image" src="#,
+            r#"<img class="item_image" srcset="https://a/small.jpg 320w, https://a/big.jpg 640w" src="#,
+        );
+        let items = parse_synthetic(&buf);
+        assert_eq!(
+            items[0].thumbnail_url,
+            Some(
+                "https://images.tori.fi/api/v1/imagestori/images/1.jpg?rule=thumb_280x210"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            items[0].thumbnail_srcset,
+            vec![
+                ("https://a/small.jpg".to_string(), 320),
+                ("https://a/big.jpg".to_string(), 640)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_price_per_unit() {
+        assert_eq!(
+            price_parse("500 €/kk"),
+            Ok(Price {
+                value: 500,
+                unit: "€".to_string(),
+                per: Some("kk".to_string()),
+                max: None,
+            })
+        );
+        assert_eq!(
+            price_parse("2 € / kpl"),
+            Ok(Price {
+                value: 2,
+                unit: "€".to_string(),
+                per: Some("kpl".to_string()),
+                max: None,
+            })
+        );
+        assert_eq!(
+            price_parse("500 €"),
+            Ok(Price {
+                value: 500,
+                unit: "€".to_string(),
+                per: None,
+                max: None,
             })
         );
     }
 
     #[test]
-    fn test_parse_month_short() {
-        assert_eq!(parse_month_short("tam"), Ok(Month::January));
+    fn test_parse_price_range() {
+        let expected = Ok(Price {
+            value: 100,
+            unit: "€".to_string(),
+            per: None,
+            max: Some(200),
+        });
+        assert_eq!(price_parse("100–200 €"), expected);
+        assert_eq!(price_parse("100 - 200 €"), expected);
+        assert_eq!(price_parse("100 — 200 €"), expected);
+        assert_eq!(
+            price_parse("1 000-2 000 €/kk"),
+            Ok(Price {
+                value: 1000,
+                unit: "€".to_string(),
+                per: Some("kk".to_string()),
+                max: Some(2000),
+            })
+        );
         assert_eq!(
-            parse_month_short("foo"),
-            Err(DateParseError::InvalidMonth("foo".to_string()))
+            price_parse("200 - 100 €"),
+            Err(ItemParseErrorKind::InvalidPrice("200 - 100 €".to_string()))
         );
     }
 
     #[test]
-    fn test_parse_hh_mm() {
+    fn test_parse_price_range_unit_per_bound() {
+        let expected = Ok(Price {
+            value: 100,
+            unit: "€".to_string(),
+            per: None,
+            max: Some(200),
+        });
+        assert_eq!(price_parse("100 € - 200 €"), expected);
+        assert_eq!(price_parse("100€–200 €"), expected);
         assert_eq!(
-            parse_hh_mm("01:23"),
-            Ok(NaiveTime::from_hms_opt(1, 23, 0).unwrap())
+            price_parse("100 kr - 200 kr/kk").map(|p| (p.unit, p.per)),
+            Ok(("kr".to_string(), Some("kk".to_string())))
         );
         assert_eq!(
-            parse_hh_mm("01:60"),
-            Err(DateParseError::InvalidTime("01:60".to_string()))
+            price_parse("100 € - 200 kr"),
+            Err(ItemParseErrorKind::PriceUnitMismatch(
+                "100 € - 200 kr".to_string()
+            ))
         );
+        assert_eq!(price_parse_opt("100 € - 200 kr"), None);
+    }
+
+    #[test]
+    fn test_parse_category() {
+        let doc = Html::parse_document(&read_dump("testdata/2023-03-25-105201-dump.html"));
+        let items = Parser::new(get_time()).parse_document(&doc).unwrap();
+        assert_eq!(
+            items[0].category,
+            Some("Mopojen varaosat ja tarvikkeet".to_string())
+        );
+        let car = items
+            .iter()
+            .find(|i| i.title.starts_with("Alfa Romeo"))
+            .unwrap();
+        assert_eq!(car.category, Some("Autot".to_string()));
+
+        // the synthetic row has no alt text
+        let items = parse_synthetic(SYNTHETIC_ROW);
+        assert_eq!(items[0].category, None);
+
+        assert_eq!(
+            category_from_alt(
+                "Reititin, modeemi, Verkkotuotteet, Tietokoneet, Lieksa, Tori.fi",
+                "Reititin, modeemi"
+            ),
+            Some("Verkkotuotteet".to_string())
+        );
+        assert_eq!(category_from_alt("Sohva, Tori.fi", "Sohva"), None);
         assert_eq!(
-            parse_hh_mm("25:24"),
-            Err(DateParseError::InvalidTime("25:24".to_string()))
+            category_from_alt("Pöytä, Sohvat, Espoo, Tori.fi", "Sohva"),
+            None
         );
     }
 
+    #[test]
+    fn test_parse_thumbnail_placeholder() {
+        use crate::testutil::RowBuilder;
+
+        let placeholder = "data:image/gif;base64,R0lGODlhAQABAAAAACw=";
+        let real = "https://images.tori.fi/api/v1/imagestori/images/1.jpg?rule=thumb_280x210";
+
+        let buf = RowBuilder::new()
+            .image(&format!(r#"src="{}" data-src="{}""#, placeholder, real))
+            .build();
+        let items = parse_synthetic(&buf);
+        assert_eq!(items[0].thumbnail_url, Some(real.to_string()));
+        assert_eq!(items[0].all_thumbnails, vec![real]);
+
+        let buf = RowBuilder::new()
+            .image(&format!(r#"src="{}""#, placeholder))
+            .build();
+        let items = parse_synthetic(&buf);
+        assert_eq!(items[0].thumbnail_url, None);
+        assert!(items[0].all_thumbnails.is_empty());
+    }
+
+    #[test]
+    fn test_parse_all_thumbnails() {
+        let items = parse_synthetic(SYNTHETIC_ROW);
+        assert_eq!(
+            items[0].all_thumbnails,
+            vec!["https://images.tori.fi/api/v1/imagestori/images/1.jpg?rule=thumb_280x210"]
+        );
+
+        let buf = SYNTHETIC_ROW.replace(
+            r#"<div class="item_image_div">"#,
+            r#"<div class="item_image_div"><img class="item_image" src="https://a/1.jpg" /></div>
+            <div class="item_image_div"><img class="item_image" src="https://a/2.jpg" /></div>
+            <div class="item_image_div"><img class="item_image" src="https://a/1.jpg" /></div>
+            <div class="item_image_div">"#,
+        );
+        let items = parse_synthetic(&buf);
+        assert_eq!(items[0].thumbnail_url, Some("https://a/1.jpg".to_string()));
+        assert_eq!(
+            items[0].all_thumbnails,
+            vec![
+                "https://a/1.jpg",
+                "https://a/2.jpg",
+                "https://images.tori.fi/api/v1/imagestori/images/1.jpg?rule=thumb_280x210"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_item_attribute_as_str() {
+        let codes: Vec<&str> = ItemAttribute::ALL.iter().map(|a| a.as_str()).collect();
+        assert_eq!(
+            codes,
+            vec![
+                "id",
+                "title",
+                "href",
+                "company_ad",
+                "img",
+                "posted_at",
+                "location",
+                "direction"
+            ]
+        );
+        let unique: std::collections::HashSet<&str> = codes.iter().copied().collect();
+        assert_eq!(unique.len(), codes.len());
+    }
+
+    #[test]
+    fn test_price_ord() {
+        let price = |value: i32| price_parse(&format!("{} €", value)).unwrap();
+        assert!(price(50) < price(100));
+        assert!(price(100) > price(50));
+        assert_eq!(price(50).cmp(&price(50)), Ordering::Equal);
+
+        let mut prices = vec![price(100), price(5), price(50)];
+        prices.sort();
+        assert_eq!(prices, vec![price(5), price(50), price(100)]);
+        assert_eq!(prices.iter().max(), Some(&price(100)));
+
+        // a range sorts after a fixed price with the same lower bound
+        let range = price_parse("50–80 €").unwrap();
+        assert!(price(50) < range);
+        assert!(range < price(51));
+    }
+
+    #[test]
+    fn test_price_currency_code() {
+        let price = Price {
+            value: 1,
+            unit: "€".to_string(),
+            per: None,
+            max: None,
+        };
+        assert_eq!(price.currency_code(), Some("EUR"));
+
+        let price = Price {
+            value: 1,
+            unit: "SEK".to_string(),
+            per: None,
+            max: None,
+        };
+        assert_eq!(price.currency_code(), Some("SEK"));
+
+        let price = Price {
+            value: 1,
+            unit: "kr".to_string(),
+            per: None,
+            max: None,
+        };
+        assert_eq!(price.currency_code(), None);
+
+        let price = Price {
+            value: 1,
+            unit: "¤".to_string(),
+            per: None,
+            max: None,
+        };
+        assert_eq!(price.currency_code(), None);
+    }
+
     fn get_time() -> DateTime<Tz> {
         utils::timezone_lookup("Europe/Helsinki")
             .unwrap()
@@ -479,6 +2868,104 @@ mod tests {
             .unwrap()
     }
 
+    #[test]
+    fn test_parser_fetch_time() {
+        let parser = Parser::new(get_time());
+        assert_eq!(parser.fetch_time(), get_time());
+        assert_eq!(parser.timezone(), chrono_tz::Europe::Helsinki);
+
+        let parser = Parser::from_utc(get_time().with_timezone(&Utc), chrono_tz::Europe::Helsinki);
+        assert_eq!(parser.fetch_time(), get_time());
+    }
+
+    #[test]
+    fn test_parser_from_utc() {
+        let fetch_time = get_time();
+        let local = Parser::new(fetch_time);
+        let utc = Parser::from_utc(fetch_time.with_timezone(&Utc), chrono_tz::Europe::Helsinki);
+
+        assert_eq!(
+            utc.parse_posted_at("tänään 01:23"),
+            local.parse_posted_at("tänään 01:23")
+        );
+        assert_eq!(
+            utc.parse_posted_at("eilen 15:59"),
+            local.parse_posted_at("eilen 15:59")
+        );
+    }
+
+    #[test]
+    fn test_parse_posted_at_whitespace() {
+        let parser = Parser::new(get_time());
+        let expected = parser.parse_posted_at("tänään 01:23");
+        assert!(expected.is_ok());
+        assert_eq!(parser.parse_posted_at("  tänään   01:23  "), expected);
+        assert_eq!(parser.parse_posted_at("tänään\n\t01:23"), expected);
+    }
+
+    #[test]
+    fn test_parser_server_time_and_timezone() {
+        use chrono::Datelike;
+
+        // still the 24th in UTC and in New York, already the 25th in Helsinki
+        let server_time = Utc.with_ymd_and_hms(2023, 3, 24, 23, 30, 0).unwrap();
+        let helsinki = Parser::from_utc(server_time, chrono_tz::Europe::Helsinki);
+        let new_york = Parser::from_utc(server_time, chrono_tz::America::New_York);
+
+        assert_eq!(helsinki.server_time(), server_time);
+        assert_eq!(new_york.server_time(), server_time);
+        assert_eq!(helsinki.fetch_time().date_naive().day(), 25);
+        assert_eq!(new_york.fetch_time().date_naive().day(), 24);
+
+        // the relative day follows the user's calendar
+        assert_eq!(
+            helsinki.parse_posted_at("tänään 01:00"),
+            Ok(Utc.with_ymd_and_hms(2023, 3, 24, 23, 0, 0).unwrap())
+        );
+        assert_eq!(
+            new_york.parse_posted_at("tänään 01:00"),
+            Ok(Utc.with_ymd_and_hms(2023, 3, 24, 5, 0, 0).unwrap())
+        );
+
+        // 01:45 in Helsinki is 23:45 UTC, after the server time, so it must be last year's
+        assert_eq!(
+            helsinki.parse_posted_at("25 maa 01:45"),
+            Ok(Utc.with_ymd_and_hms(2022, 3, 24, 23, 45, 0).unwrap())
+        );
+        assert_eq!(
+            helsinki.parse_posted_at("25 maa 01:15"),
+            Ok(Utc.with_ymd_and_hms(2023, 3, 24, 23, 15, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_relative_day_words() {
+        let parser = Parser::new(get_time()).with_relative_day_words("today", "yesterday");
+        let default = Parser::new(get_time());
+
+        assert_eq!(
+            parser.parse_posted_at("today 01:23"),
+            default.parse_posted_at("tänään 01:23")
+        );
+        assert_eq!(
+            parser.parse_posted_at("yesterday 15:59"),
+            default.parse_posted_at("eilen 15:59")
+        );
+        // the locale's own words still work
+        assert!(parser.parse_posted_at("tänään 01:23").is_ok());
+
+        // a locale set afterwards keeps the words
+        let parser = parser.with_locale(Box::new(crate::locale::Swedish));
+        assert_eq!(
+            parser.parse_posted_at("today 01:23"),
+            default.parse_posted_at("tänään 01:23")
+        );
+        assert_eq!(
+            default.parse_posted_at("today 01:23"),
+            Err(DateParseError::InvalidRelativeDay("today".to_string()))
+        );
+    }
+
     #[test]
     fn test_parse_ts_relative() {
         let parser = Parser::new(get_time());
@@ -510,26 +2997,32 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_ts_absolute() {
-        let parser = Parser::new(get_time());
-        let result = parser.parse_posted_at("21 huh 19:52");
-        assert_eq!(
-            result,
-            Ok(chrono_tz::Europe::Helsinki
-                .with_ymd_and_hms(2022, 4, 21, 19, 52, 0)
-                .unwrap()
-                .with_timezone(&Utc))
-        );
-        let result = parser.parse_posted_at("32 tam 01:32");
-        assert_eq!(result, Err(DateParseError::InvalidDay("32".to_string())));
-    }
+    fn test_item_to_json_map() {
+        let items = parse_synthetic(SYNTHETIC_ROW);
+        let map = items[0].to_json_map();
 
-    #[test]
-    fn test_parse_day() {
-        assert!(parse_day("0").is_err());
-        assert!(parse_day("32").is_err());
-        assert!(parse_day("1").unwrap() == 1);
-        assert!(parse_day("31").unwrap() == 31);
+        assert_eq!(map["item_id"], "123");
+        assert_eq!(map["direction"], "Myydään");
+        assert_eq!(map["title"], "Sohva");
+        assert!(map["description"].is_null());
+        assert_eq!(map["price_value"], 50);
+        assert_eq!(map["price_unit"], "€");
+        assert!(map["price_per"].is_null());
+        assert!(map["price_max"].is_null());
+        assert_eq!(map["price_present"], true);
+        assert_eq!(map["price_kind"], "fixed");
+        assert!(map["price_raw"].is_null());
+        assert_eq!(map["location"], "Uusimaa");
+        assert!(map["category"].is_null());
+        assert!(map["seller"].is_null());
+        assert!(map["seller_href"].is_null());
+        assert_eq!(map["is_company_ad"], false);
+        assert!(map["href"].is_string());
+        assert!(map["thumbnail_url"].is_string());
+        assert_eq!(map["posted_at_orig"], "tänään 12:34");
+        assert_eq!(map["posted_at"], "2023-03-25T10:34:00+00:00");
+        assert!(map["bumped_at"].is_null());
+        assert!(map.values().all(|v| !v.is_object() && !v.is_array()));
     }
 
     #[test]
@@ -0,0 +1,142 @@
+use std::error::Error;
+use std::fmt;
+
+use url::Url;
+
+/// Search criteria encoded in a tori.fi search url, see [parse_search_url].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SearchQuery {
+    /// Region from the url path, e.g. `"koko_suomi"` or `"uusimaa"`.
+    pub region: Option<String>,
+    /// Search words (`q`).
+    pub keywords: Option<String>,
+    /// Category id (`cg`).
+    pub category: Option<u32>,
+    /// Subcategory id (`c`).
+    pub subcategory: Option<u32>,
+    /// Area code (`ca`).
+    pub area: Option<u32>,
+    /// Extent of the search around the area (`w`).
+    pub scope: Option<u32>,
+    /// Listing types (`st`), e.g. `s` for selling and `k` for buying.
+    pub listing_types: Vec<String>,
+    /// Results page number (`o`).
+    pub page: Option<u32>,
+    /// Any other query parameters, as is.
+    pub other: Vec<(String, String)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UrlError {
+    /// The input is not a valid url.
+    Invalid(String),
+    /// The url does not point to tori.fi.
+    NotTori(String),
+    /// A known parameter has an unexpected value, as (name, value).
+    InvalidParam(String, String),
+}
+
+impl fmt::Display for UrlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UrlError::Invalid(url) => write!(f, "invalid url: '{}'", url),
+            UrlError::NotTori(url) => write!(f, "not a tori.fi url: '{}'", url),
+            UrlError::InvalidParam(name, value) => {
+                write!(f, "invalid value for parameter '{}': '{}'", name, value)
+            }
+        }
+    }
+}
+
+impl Error for UrlError {}
+
+/// Decodes the search criteria of a tori.fi search url such as
+/// `https://www.tori.fi/koko_suomi?ca=18&q=mopo&w=3`.
+pub fn parse_search_url(url: &str) -> Result<SearchQuery, UrlError> {
+    let parsed = Url::parse(url).map_err(|_| UrlError::Invalid(url.to_string()))?;
+    match parsed.host_str() {
+        Some(host) if host == "tori.fi" || host.ends_with(".tori.fi") => {}
+        _ => return Err(UrlError::NotTori(url.to_string())),
+    }
+
+    let number = |name: &str, value: &str| {
+        value
+            .parse::<u32>()
+            .map_err(|_| UrlError::InvalidParam(name.to_string(), value.to_string()))
+    };
+
+    let mut query = SearchQuery {
+        region: parsed
+            .path_segments()
+            .and_then(|mut segments| segments.find(|s| !s.is_empty()))
+            .map(|s| s.to_string()),
+        ..SearchQuery::default()
+    };
+    for (name, value) in parsed.query_pairs() {
+        match name.as_ref() {
+            "q" => query.keywords = Some(value.into_owned()).filter(|q| !q.is_empty()),
+            "cg" => query.category = Some(number(&name, &value)?),
+            "c" => query.subcategory = Some(number(&name, &value)?),
+            "ca" => query.area = Some(number(&name, &value)?),
+            "w" => query.scope = Some(number(&name, &value)?),
+            "o" => query.page = Some(number(&name, &value)?),
+            "st" => query.listing_types.push(value.into_owned()),
+            _ => query.other.push((name.into_owned(), value.into_owned())),
+        }
+    }
+    Ok(query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_search_url() {
+        let query =
+            parse_search_url("https://www.tori.fi/koko_suomi?ca=18&q=mopo&w=3&o=2").unwrap();
+        assert_eq!(
+            query,
+            SearchQuery {
+                region: Some("koko_suomi".to_string()),
+                keywords: Some("mopo".to_string()),
+                area: Some(18),
+                scope: Some(3),
+                page: Some(2),
+                ..SearchQuery::default()
+            }
+        );
+
+        let query = parse_search_url(
+            "https://www.tori.fi/uusimaa?q=sohva+nahka&cg=3020&c=3021&st=s&st=g&ca=18&w=1&f=p",
+        )
+        .unwrap();
+        assert_eq!(query.region, Some("uusimaa".to_string()));
+        assert_eq!(query.keywords, Some("sohva nahka".to_string()));
+        assert_eq!(query.category, Some(3020));
+        assert_eq!(query.subcategory, Some(3021));
+        assert_eq!(query.listing_types, vec!["s", "g"]);
+        assert_eq!(query.other, vec![("f".to_string(), "p".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_search_url_errors() {
+        assert_eq!(
+            parse_search_url("not a url"),
+            Err(UrlError::Invalid("not a url".to_string()))
+        );
+        assert_eq!(
+            parse_search_url("https://example.com/koko_suomi?q=mopo"),
+            Err(UrlError::NotTori(
+                "https://example.com/koko_suomi?q=mopo".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_search_url("https://www.tori.fi/koko_suomi?cg=huonekalut"),
+            Err(UrlError::InvalidParam(
+                "cg".to_string(),
+                "huonekalut".to_string()
+            ))
+        );
+    }
+}
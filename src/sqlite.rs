@@ -0,0 +1,90 @@
+use rusqlite::{params, Connection};
+
+use crate::parsing::Item;
+
+const CREATE_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS items (
+    item_id TEXT PRIMARY KEY,
+    direction TEXT NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT,
+    price_value INTEGER,
+    price_unit TEXT,
+    location TEXT NOT NULL,
+    seller TEXT,
+    is_company_ad INTEGER NOT NULL,
+    href TEXT NOT NULL,
+    thumbnail_url TEXT,
+    posted_at TEXT NOT NULL
+)";
+
+/// Writes the items into table `items` of the given database, creating the table if it does
+/// not exist. Rows are keyed by `item_id`; existing rows are replaced. `posted_at` is stored
+/// as an RFC 3339 string, the price as separate value and unit columns.
+pub fn export_to_sqlite(items: &[Item], conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(CREATE_TABLE, [])?;
+
+    let mut stmt = conn.prepare(
+        "INSERT OR REPLACE INTO items (item_id, direction, title, description, price_value,
+            price_unit, location, seller, is_company_ad, href, thumbnail_url, posted_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+    )?;
+    for item in items {
+        stmt.execute(params![
+            item.item_id,
+            item.direction,
+            item.title,
+            item.description,
+            item.price.as_ref().map(|p| p.value),
+            item.price.as_ref().map(|p| &p.unit),
+            item.location,
+            item.seller,
+            item.is_company_ad,
+            item.href,
+            item.thumbnail_url,
+            item.posted_at.to_rfc3339(),
+        ])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+    use crate::utils;
+    use chrono::TimeZone;
+    use std::path::Path;
+
+    #[test]
+    fn test_export_to_sqlite() {
+        let parent = Path::new(file!()).parent().unwrap();
+        let buf = utils::decode_to_string(
+            &parent.join("testdata/2023-03-25-105201-dump.html"),
+            utils::encoding_lookup("ISO_8859_15").unwrap(),
+        );
+        let helsinki = chrono_tz::Europe::Helsinki;
+        let parser = Parser::new(helsinki.with_ymd_and_hms(2023, 3, 25, 10, 52, 1).unwrap());
+        let items = parser.parse_from_string(&buf).unwrap();
+
+        let conn = Connection::open_in_memory().unwrap();
+        export_to_sqlite(&items, &conn).unwrap();
+        // exporting again replaces the rows instead of duplicating them
+        export_to_sqlite(&items, &conn).unwrap();
+
+        let count: usize = conn
+            .query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 40);
+
+        let (title, posted_at): (String, String) = conn
+            .query_row(
+                "SELECT title, posted_at FROM items WHERE item_id = ?1",
+                [&items[0].item_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(title, items[0].title);
+        assert_eq!(posted_at, items[0].posted_at.to_rfc3339());
+    }
+}
@@ -0,0 +1,203 @@
+//! Helpers for constructing synthetic search results markup in tests.
+
+/// Builds a minimal item row (`a[data-row]` element) matching the default [Selectors].
+/// All fields start with valid values, so tests only need to override the one they exercise.
+/// Values are inserted into the markup verbatim, without escaping.
+///
+/// [Selectors]: crate::Selectors
+pub(crate) struct RowBuilder {
+    id: String,
+    title: String,
+    price: Option<String>,
+    company_ad: String,
+    posted_at: String,
+    bumped_at: Option<String>,
+    image: Option<String>,
+    has_href: bool,
+    cat_geo: Vec<String>,
+}
+
+impl Default for RowBuilder {
+    fn default() -> Self {
+        RowBuilder {
+            id: "123".to_string(),
+            title: "Sohva".to_string(),
+            price: Some("50 &euro;".to_string()),
+            company_ad: "0".to_string(),
+            posted_at: "tänään 12:34".to_string(),
+            bumped_at: None,
+            image: None,
+            has_href: true,
+            cat_geo: vec!["Uusimaa".to_string(), "Myydään".to_string()],
+        }
+    }
+}
+
+impl RowBuilder {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Item id, without the `item_` prefix.
+    pub(crate) fn id(mut self, id: &str) -> Self {
+        self.id = id.to_string();
+        self
+    }
+
+    pub(crate) fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Contents of the price element.
+    pub(crate) fn price(mut self, price: &str) -> Self {
+        self.price = Some(price.to_string());
+        self
+    }
+
+    /// Omit the price element entirely.
+    pub(crate) fn no_price(mut self) -> Self {
+        self.price = None;
+        self
+    }
+
+    /// Raw value of the `data-company-ad` attribute; `"0"` or `"1"` when valid.
+    pub(crate) fn company_ad(mut self, value: &str) -> Self {
+        self.company_ad = value.to_string();
+        self
+    }
+
+    pub(crate) fn posted_at(mut self, posted_at: &str) -> Self {
+        self.posted_at = posted_at.to_string();
+        self
+    }
+
+    /// Contents of the bump time element; the element is omitted by default.
+    pub(crate) fn bumped_at(mut self, bumped_at: &str) -> Self {
+        self.bumped_at = Some(bumped_at.to_string());
+        self
+    }
+
+    /// Attributes of the thumbnail `img`, e.g. `src="..."`; no thumbnail by default.
+    pub(crate) fn image(mut self, attrs: &str) -> Self {
+        self.image = Some(attrs.to_string());
+        self
+    }
+
+    /// Omit the `href` attribute of the row.
+    pub(crate) fn no_href(mut self) -> Self {
+        self.has_href = false;
+        self
+    }
+
+    /// Texts of the `cat_geo` paragraphs, each wrapped in its own `<p>`.
+    pub(crate) fn cat_geo(mut self, paragraphs: &[&str]) -> Self {
+        self.cat_geo = paragraphs.iter().map(|p| p.to_string()).collect();
+        self
+    }
+
+    pub(crate) fn build(&self) -> String {
+        let price = match &self.price {
+            Some(price) => format!(r#"<p class="list_price ineuros">{}</p>"#, price),
+            None => String::new(),
+        };
+        let href = match self.has_href {
+            true => format!(r#" href="https://www.tori.fi/uusimaa/{}.htm""#, self.id),
+            false => String::new(),
+        };
+        let image = match &self.image {
+            Some(attrs) => format!(
+                r#"<div class="image_container"><img class="item_image" {} /></div>"#,
+                attrs
+            ),
+            None => String::new(),
+        };
+        let bumped_at = match &self.bumped_at {
+            Some(bumped_at) => format!(r#"<div class="bumped_date">{}</div>"#, bumped_at),
+            None => String::new(),
+        };
+        let cat_geo: String = self
+            .cat_geo
+            .iter()
+            .map(|p| format!("<p>{}</p>", p))
+            .collect();
+        format!(
+            r#"<a id="item_{id}"{href} class="item_row_flex" data-row="1" data-company-ad="{company_ad}">
+    {image}
+    <div class="desc_flex">
+        <div class="li-title">{title}</div>
+        {price}
+        <div class="date_image">{posted_at}</div>
+        {bumped_at}
+        <div class="cat_geo clean_links">{cat_geo}</div>
+    </div>
+</a>
+"#,
+            id = self.id,
+            href = href,
+            image = image,
+            bumped_at = bumped_at,
+            company_ad = self.company_ad,
+            title = self.title,
+            price = price,
+            posted_at = self.posted_at,
+            cat_geo = cat_geo,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::{ItemAttribute, ItemParseErrorKind, Parser};
+    use chrono::TimeZone;
+
+    fn parser() -> Parser {
+        let helsinki = chrono_tz::Europe::Helsinki;
+        Parser::new(helsinki.with_ymd_and_hms(2023, 3, 25, 10, 52, 1).unwrap())
+    }
+
+    #[test]
+    fn test_row_builder() {
+        let buf = RowBuilder::new()
+            .id("42")
+            .title("Pöytä")
+            .price("75 &euro;")
+            .company_ad("1")
+            .posted_at("eilen 08:00")
+            .cat_geo(&["Espoo", "Ostetaan", "Huonekalu Oy"])
+            .build();
+        let items = parser().parse_from_string(&buf).unwrap();
+
+        assert_eq!(items.len(), 1);
+        let item = &items[0];
+        assert_eq!(item.item_id, "42");
+        assert_eq!(item.title, "Pöytä");
+        assert_eq!(item.price.as_ref().map(|p| p.value), Some(75));
+        assert!(item.is_company_ad);
+        assert_eq!(item.posted_at_orig, "eilen 08:00");
+        assert_eq!(item.location, "Espoo");
+        assert_eq!(item.direction, "Ostetaan");
+        assert_eq!(item.seller, Some("Huonekalu Oy".to_string()));
+    }
+
+    #[test]
+    fn test_row_builder_broken() {
+        let buf = RowBuilder::new().company_ad("x").build();
+        let err = parser().parse_from_string(&buf).unwrap_err();
+        assert_eq!(
+            err.error,
+            ItemParseErrorKind::UnexpectedValue(ItemAttribute::CompanyAd, "x".to_string())
+        );
+
+        let buf = RowBuilder::new().no_price().build();
+        assert!(!parser().parse_from_string(&buf).unwrap()[0].price_present);
+
+        let buf = RowBuilder::new().no_href().build();
+        let err = parser().parse_from_string(&buf).unwrap_err();
+        assert_eq!(
+            err.error,
+            ItemParseErrorKind::MissingAttribute(ItemAttribute::Href)
+        );
+    }
+}
@@ -1,12 +1,24 @@
+use std::error::Error;
+use std::fmt;
 use std::fs;
+#[cfg(feature = "html")]
+use std::io;
 use std::io::BufReader;
 use std::io::Read;
+#[cfg(feature = "html")]
+use std::io::Write;
 use std::path::Path;
+#[cfg(feature = "html")]
+use std::path::PathBuf;
 
+use chrono::{DateTime, NaiveDateTime, TimeZone};
 use chrono_tz::Tz;
 use encoding_rs;
 use encoding_rs_io::DecodeReaderBytesBuilder;
 
+#[cfg(feature = "html")]
+use crate::{Item, ItemParseError, Parser};
+
 /// Reads given file (assumed to be in given encoding), and transcodes it to native UTF-8 String.
 pub fn decode_to_string(path: &Path, encoding: &'static encoding_rs::Encoding) -> String {
     let file = fs::File::open(path).unwrap();
@@ -26,17 +38,124 @@ pub fn decode_to_string(path: &Path, encoding: &'static encoding_rs::Encoding) -
     buf
 }
 
-/// Lookup locale encoding using conventional string labels such as "ISO_8859_15"
+/// Like [decode_to_string], but for gzip-compressed files.
+#[cfg(feature = "gzip")]
+pub fn decode_gzip_to_string(path: &Path, encoding: &'static encoding_rs::Encoding) -> String {
+    let file = fs::File::open(path).unwrap();
+
+    let transcoded = DecodeReaderBytesBuilder::new()
+        .encoding(Some(encoding))
+        .build(flate2::read::GzDecoder::new(file));
+
+    let mut reader = BufReader::new(transcoded);
+
+    let mut buf = String::new();
+
+    let n = reader.read_to_string(&mut buf).unwrap();
+
+    assert!(n > 0);
+
+    buf
+}
+
+/// Reads given file and decodes it with both `primary` and `fallback` encodings, returning
+/// whichever output contains fewer replacement characters (U+FFFD) along with the encoding
+/// that produced it. Ties go to `primary`.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use tori_scrape::utils::decode_with_fallback;
+///
+/// let path = Path::new("src/testdata/2023-03-25-105201-dump.html");
+/// let (buf, encoding) =
+///     decode_with_fallback(path, encoding_rs::UTF_8, encoding_rs::ISO_8859_15);
+/// assert_eq!(encoding, encoding_rs::ISO_8859_15);
+/// assert!(!buf.contains('\u{FFFD}'));
+/// ```
+pub fn decode_with_fallback(
+    path: &Path,
+    primary: &'static encoding_rs::Encoding,
+    fallback: &'static encoding_rs::Encoding,
+) -> (String, &'static encoding_rs::Encoding) {
+    decode_bytes_with_fallback(&fs::read(path).unwrap(), primary, fallback)
+}
+
+/// The decoding step of [decode_with_fallback], for bytes that have already been read.
+fn decode_bytes_with_fallback(
+    bytes: &[u8],
+    primary: &'static encoding_rs::Encoding,
+    fallback: &'static encoding_rs::Encoding,
+) -> (String, &'static encoding_rs::Encoding) {
+    let decode = |encoding: &'static encoding_rs::Encoding| {
+        let (text, _) = encoding.decode_without_bom_handling(bytes);
+        let replacements = text.matches('\u{FFFD}').count();
+        (text.into_owned(), replacements)
+    };
+
+    let (primary_buf, primary_errors) = decode(primary);
+    if primary_errors == 0 {
+        return (primary_buf, primary);
+    }
+
+    let (fallback_buf, fallback_errors) = decode(fallback);
+    if fallback_errors < primary_errors {
+        (fallback_buf, fallback)
+    } else {
+        (primary_buf, primary)
+    }
+}
+
+/// Lookup locale encoding using conventional string labels such as "ISO_8859_15". Besides
+/// "ISO_8859_15", any [WHATWG encoding label](https://encoding.spec.whatwg.org/#names-and-labels)
+/// is accepted. Returns `None` for unknown labels; there is no implicit fallback to UTF-8.
+///
+/// # Examples
+///
+/// ```
+/// use tori_scrape::utils::encoding_lookup;
+///
+/// assert_eq!(encoding_lookup("ISO_8859_15"), Some(encoding_rs::ISO_8859_15));
+/// assert_eq!(encoding_lookup("ISO-8859-15"), Some(encoding_rs::ISO_8859_15));
+/// assert_eq!(encoding_lookup("utf-8"), Some(encoding_rs::UTF_8));
+/// assert_eq!(encoding_lookup("no-such-encoding"), None);
+/// ```
 pub fn encoding_lookup(name: &str) -> Option<&'static encoding_rs::Encoding> {
     match name {
         "ISO_8859_15" => Some(encoding_rs::ISO_8859_15),
-        _ => Some(encoding_rs::UTF_8),
+        _ => encoding_rs::Encoding::for_label(name.as_bytes()),
     }
 }
 
+/// Error returned by [timezone_lookup] for unknown timezone names. Carries the offending name.
+#[derive(Debug, PartialEq)]
+pub struct TimezoneLookupError(pub String);
+
+impl fmt::Display for TimezoneLookupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown timezone: '{}'", self.0)
+    }
+}
+
+impl Error for TimezoneLookupError {}
+
 /// Lookup timezone using conventional string labels such as "Europe/Helsinnki"
-pub fn timezone_lookup(name: &str) -> Result<Tz, String> {
+///
+/// # Examples
+///
+/// ```
+/// use tori_scrape::utils::{timezone_lookup, TimezoneLookupError};
+///
+/// assert!(timezone_lookup("Europe/Helsinki").is_ok());
+/// assert_eq!(
+///     timezone_lookup("Mars/Olympus"),
+///     Err(TimezoneLookupError("Mars/Olympus".to_string()))
+/// );
+/// ```
+pub fn timezone_lookup(name: &str) -> Result<Tz, TimezoneLookupError> {
     name.parse::<Tz>()
+        .map_err(|_| TimezoneLookupError(name.to_string()))
 }
 
 /// Takes a string with uncontrolled amount of whitespace between tokens,
@@ -54,3 +173,129 @@ pub fn reformat_ws(input: &str) -> String {
     let w = input.split_whitespace();
     w.collect::<Vec<&str>>().join(" ")
 }
+
+/// Writes the items into `writer` as a single JSON array, each item in the format of
+/// [Item::to_json_map]. Items are serialized and flushed one at a time, so the whole output
+/// never needs to be held in memory.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use chrono::TimeZone;
+/// use tori_scrape::utils::{decode_to_string, encoding_lookup, write_json_array};
+/// use tori_scrape::Parser;
+///
+/// let buf = decode_to_string(
+///     Path::new("src/testdata/2023-03-25-105201-dump.html"),
+///     encoding_lookup("ISO_8859_15").unwrap(),
+/// );
+/// let fetch_time = chrono_tz::Europe::Helsinki
+///     .with_ymd_and_hms(2023, 3, 25, 10, 52, 1)
+///     .unwrap();
+/// let items = Parser::new(fetch_time).parse_from_string(&buf).unwrap();
+///
+/// let mut out = Vec::new();
+/// write_json_array(items.iter(), &mut out).unwrap();
+///
+/// let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+/// assert_eq!(json.as_array().unwrap().len(), 40);
+/// assert_eq!(json[0]["item_id"], items[0].item_id.as_str());
+/// ```
+#[cfg(feature = "html")]
+pub fn write_json_array<'a, W: Write>(
+    items: impl Iterator<Item = &'a Item>,
+    mut writer: W,
+) -> io::Result<()> {
+    writer.write_all(b"[")?;
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            writer.write_all(b",")?;
+        }
+        serde_json::to_writer(&mut writer, &item.to_json_map())?;
+        writer.flush()?;
+    }
+    writer.write_all(b"]")?;
+    writer.flush()
+}
+
+/// Fetch time encoded in the `YYYY-MM-DD-HHMMSS` prefix of a dump file name, such as
+/// `2023-03-25-105201-dump.html`, as local time in `tz`. Returns `None` if the name has no
+/// such prefix, or the time does not exist in `tz`.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::TimeZone;
+/// use tori_scrape::utils::fetch_time_from_filename;
+///
+/// let tz = chrono_tz::Europe::Helsinki;
+/// assert_eq!(
+///     fetch_time_from_filename("2023-03-25-105201-dump.html", tz),
+///     Some(tz.with_ymd_and_hms(2023, 3, 25, 10, 52, 1).unwrap())
+/// );
+/// assert_eq!(
+///     fetch_time_from_filename("2024-01-30-123020-dump.html", tz),
+///     Some(tz.with_ymd_and_hms(2024, 1, 30, 12, 30, 20).unwrap())
+/// );
+/// assert_eq!(fetch_time_from_filename("test.json", tz), None);
+/// assert_eq!(fetch_time_from_filename("2023-13-25-105201-dump.html", tz), None);
+/// ```
+pub fn fetch_time_from_filename(name: &str, tz: Tz) -> Option<DateTime<Tz>> {
+    let prefix = name.get(.."YYYY-MM-DD-HHMMSS".len())?;
+    let naive = NaiveDateTime::parse_from_str(prefix, "%Y-%m-%d-%H%M%S").ok()?;
+    tz.from_local_datetime(&naive).single()
+}
+
+/// Parses every `*-dump.html` file in `dir`, using the fetch time encoded in its name, e.g.
+/// `2023-03-25-105201-dump.html` for a page fetched at 10:52:01 local time in `tz`. Files
+/// whose name carries no valid timestamp are skipped. Each file is decoded as UTF-8 or
+/// ISO-8859-15, whichever fits better (see [decode_with_fallback]). The results are sorted
+/// by path, i.e. by fetch time. Fails if the directory or any of the dumps cannot be read.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use tori_scrape::utils::parse_directory;
+///
+/// let results =
+///     parse_directory(Path::new("src/testdata"), chrono_tz::Europe::Helsinki).unwrap();
+/// let counts: Vec<usize> = results
+///     .iter()
+///     .map(|(_, result)| result.as_ref().unwrap().len())
+///     .collect();
+/// assert_eq!(counts, vec![40, 12]);
+///
+/// assert!(parse_directory(Path::new("no/such/dir"), chrono_tz::Europe::Helsinki).is_err());
+/// ```
+#[cfg(feature = "html")]
+pub fn parse_directory(
+    dir: &Path,
+    tz: Tz,
+) -> io::Result<Vec<(PathBuf, Result<Vec<Item>, ItemParseError>)>> {
+    let mut dumps: Vec<(PathBuf, DateTime<Tz>)> = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let fetch_time = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) if name.ends_with("-dump.html") => fetch_time_from_filename(name, tz),
+            _ => None,
+        };
+        if let Some(fetch_time) = fetch_time {
+            dumps.push((path, fetch_time));
+        }
+    }
+    dumps.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut results = vec![];
+    for (path, fetch_time) in dumps {
+        let (buf, _) = decode_bytes_with_fallback(
+            &fs::read(&path)?,
+            encoding_rs::UTF_8,
+            encoding_rs::ISO_8859_15,
+        );
+        let result = Parser::new(fetch_time).parse_from_string(&buf);
+        results.push((path, result));
+    }
+    Ok(results)
+}
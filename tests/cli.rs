@@ -0,0 +1,89 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Fresh, empty config directory for one test.
+fn config_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("tori-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn tori(config_dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tori"))
+        .env("TORI_CONFIG_DIR", config_dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn test_register_list_show() {
+    let dir = config_dir("register");
+    let url = "https://www.tori.fi/koko_suomi?ca=18&q=mopo&w=3";
+
+    let output = tori(&dir, &["register", url]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "registered search 1\n");
+    assert!(dir.join("searches.json").exists());
+
+    let output = tori(&dir, &["list"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), format!("1\t{}\n", url));
+
+    let output = tori(&dir, &["show", "1"]);
+    assert!(output.status.success());
+    let shown = stdout(&output);
+    assert!(shown.contains(&format!("url: {}", url)));
+    assert!(shown.contains("keywords: mopo"));
+
+    let output = tori(&dir, &["register", "https://www.tori.fi/uusimaa?q=sohva"]);
+    assert_eq!(stdout(&output), "registered search 2\n");
+    assert_eq!(stdout(&tori(&dir, &["list"])).lines().count(), 2);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_deactivate() {
+    let dir = config_dir("deactivate");
+    let first = "https://www.tori.fi/koko_suomi?q=mopo";
+    let second = "https://www.tori.fi/uusimaa?q=sohva";
+    tori(&dir, &["register", first]);
+    tori(&dir, &["register", second]);
+
+    let output = tori(&dir, &["deactivate", "1"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "deactivated search 1\n");
+
+    assert_eq!(
+        stdout(&tori(&dir, &["list", "--only", "inactive"])),
+        format!("1\t{} (inactive)\n", first)
+    );
+    assert_eq!(
+        stdout(&tori(&dir, &["list", "--only", "active"])),
+        format!("2\t{}\n", second)
+    );
+    assert!(stdout(&tori(&dir, &["show", "1"])).contains("active: false"));
+    assert!(!tori(&dir, &["deactivate", "3"]).status.success());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_show_unknown_and_invalid_url() {
+    let dir = config_dir("errors");
+
+    assert!(!tori(&dir, &["show", "1"]).status.success());
+    assert!(!tori(&dir, &["register", "https://example.com/"])
+        .status
+        .success());
+    assert!(stdout(&tori(&dir, &["list"])).is_empty());
+
+    let _ = fs::remove_dir_all(&dir);
+}